@@ -0,0 +1,26 @@
+// Reference implementation of the `crc16` hash, generated from its definition in
+// src/scheme.rs by `cargo run -- implement hash-implementations`.
+#include <cstdint>
+#include <iostream>
+#include <string>
+
+std::string hash(const std::string &s) {
+    uint32_t h = 0xffffu;
+    for (unsigned char ch : s) {
+        uint32_t c = ch;
+        h = h ^ ((c & 0xffu) << 8);
+        for (int i = 0; i < 8; i++) {
+            h = (h & 0x8000u) != 0 ? ((h << 1) ^ 0x1021u) : (h << 1);
+        }
+        h = h & 0xffffu;
+    }
+
+    return std::to_string(h);
+}
+
+int main() {
+    std::string line;
+    while (std::getline(std::cin, line)) {
+        std::cout << hash(line) << "\n";
+    }
+}
@@ -0,0 +1,29 @@
+// Reference implementation of the `crc16` hash, generated from its definition in
+// src/scheme.rs by `cargo run -- implement hash-implementations`.
+import java.io.BufferedReader;
+import java.io.InputStreamReader;
+
+class Crc16 {
+    static String hash(String s) {
+        int h = 0xffff;
+        for (int c : s.getBytes()) {
+            c &= 0xff;
+            h = h ^ ((c & 0xff) << 8);
+            for (int i = 0; i < 8; i++) {
+                h = (h & 0x8000) != 0 ? ((h << 1) ^ 0x1021) : (h << 1);
+            }
+            h = h & 0xffff;
+        }
+
+        return Integer.toUnsignedString(h);
+    }
+
+    public static void main(String[] args) throws Exception {
+        BufferedReader reader = new BufferedReader(new InputStreamReader(System.in));
+
+        String line;
+        while ((line = reader.readLine()) != null) {
+            System.out.println(hash(line));
+        }
+    }
+}
@@ -0,0 +1,27 @@
+// Reference implementation of the `crc16` hash, generated from its definition in
+// src/scheme.rs by `cargo run -- implement hash-implementations`.
+
+function hash(s) {
+    let h = 0xffff;
+    for (const ch of s) {
+        const c = ch.codePointAt(0);
+        h = (h ^ ((((c & 0xff) >>> 0) << 8) >>> 0)) >>> 0;
+        for (let i = 0; i < 8; i++) {
+            h = ((h & 0x8000) >>> 0) !== 0 ? ((((h << 1) >>> 0) ^ 0x1021) >>> 0) : ((h << 1) >>> 0);
+        }
+        h = (h & 0xffff) >>> 0;
+    }
+
+    return String(h);
+}
+
+if (require.main === module) {
+    const lines = require("fs").readFileSync(0, "utf8").split("\n");
+    if (lines[lines.length - 1] === "") {
+        lines.pop();
+    }
+
+    for (const line of lines) {
+        console.log(hash(line));
+    }
+}
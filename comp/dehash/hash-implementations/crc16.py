@@ -0,0 +1,20 @@
+# Reference implementation of the `crc16` hash, generated from its definition in
+# src/scheme.rs by `cargo run -- implement hash-implementations`.
+import sys
+
+
+def hash(s):
+    h = 0xffff
+    for ch in s:
+        c = ord(ch)
+        h = h ^ (((c & 0xff) << 8) & 0xffffffff)
+        for _ in range(8):
+            h = (((h << 1) & 0xffffffff) ^ 0x1021) if (h & 0x8000) != 0 else ((h << 1) & 0xffffffff)
+        h = h & 0xffff
+
+    return str(h)
+
+
+if __name__ == "__main__":
+    for line in sys.stdin:
+        print(hash(line.rstrip("\n")))
@@ -0,0 +1,23 @@
+// Reference implementation of the `crc16` hash, generated from its definition in
+// src/scheme.rs by `cargo run -- implement hash-implementations`.
+use std::io::BufRead;
+
+fn hash(s: &str) -> String {
+    let mut h: u32 = 0xffff;
+    for c in s.chars() {
+        let c = c as u32;
+        h = h ^ ((c & 0xff) << 8);
+        for _ in 0..8 {
+            h = if (h & 0x8000) != 0 { (h << 1) ^ 0x1021 } else { h << 1 };
+        }
+        h = h & 0xffff;
+    }
+
+    h.to_string()
+}
+
+fn main() {
+    for line in std::io::stdin().lock().lines() {
+        println!("{}", hash(&line.unwrap()));
+    }
+}
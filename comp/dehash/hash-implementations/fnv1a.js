@@ -0,0 +1,23 @@
+// Reference implementation of the `fnv1a` hash, generated from its definition in
+// src/scheme.rs by `cargo run -- implement hash-implementations`.
+
+function hash(s) {
+    let h = 0x811c9dc5;
+    for (const ch of s) {
+        const c = ch.codePointAt(0);
+        h = Math.imul((h ^ c) >>> 0, 0x1000193) >>> 0;
+    }
+
+    return String(h);
+}
+
+if (require.main === module) {
+    const lines = require("fs").readFileSync(0, "utf8").split("\n");
+    if (lines[lines.length - 1] === "") {
+        lines.pop();
+    }
+
+    for (const line of lines) {
+        console.log(hash(line));
+    }
+}
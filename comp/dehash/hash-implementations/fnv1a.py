@@ -0,0 +1,17 @@
+# Reference implementation of the `fnv1a` hash, generated from its definition in
+# src/scheme.rs by `cargo run -- implement hash-implementations`.
+import sys
+
+
+def hash(s):
+    h = 0x811c9dc5
+    for ch in s:
+        c = ord(ch)
+        h = ((h ^ c) * 0x1000193) & 0xffffffff
+
+    return str(h)
+
+
+if __name__ == "__main__":
+    for line in sys.stdin:
+        print(hash(line.rstrip("\n")))
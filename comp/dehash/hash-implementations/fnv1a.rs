@@ -0,0 +1,19 @@
+// Reference implementation of the `fnv1a` hash, generated from its definition in
+// src/scheme.rs by `cargo run -- implement hash-implementations`.
+use std::io::BufRead;
+
+fn hash(s: &str) -> String {
+    let mut h: u32 = 0x811c9dc5;
+    for c in s.chars() {
+        let c = c as u32;
+        h = (h ^ c).wrapping_mul(0x1000193);
+    }
+
+    h.to_string()
+}
+
+fn main() {
+    for line in std::io::stdin().lock().lines() {
+        println!("{}", hash(&line.unwrap()));
+    }
+}
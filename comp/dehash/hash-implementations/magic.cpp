@@ -0,0 +1,22 @@
+// Reference implementation of the `magic` hash, generated from its definition in
+// src/scheme.rs by `cargo run -- implement hash-implementations`.
+#include <cstdint>
+#include <iostream>
+#include <string>
+
+std::string hash(const std::string &s) {
+    uint32_t h = 0u;
+    for (unsigned char ch : s) {
+        uint32_t c = ch;
+        h = ((h + (c * 123123u)) ^ 123123u) << 2;
+    }
+
+    return std::to_string(h);
+}
+
+int main() {
+    std::string line;
+    while (std::getline(std::cin, line)) {
+        std::cout << hash(line) << "\n";
+    }
+}
@@ -0,0 +1,25 @@
+// Reference implementation of the `magic` hash, generated from its definition in
+// src/scheme.rs by `cargo run -- implement hash-implementations`.
+import java.io.BufferedReader;
+import java.io.InputStreamReader;
+
+class Magic {
+    static String hash(String s) {
+        int h = 0;
+        for (int c : s.getBytes()) {
+            c &= 0xff;
+            h = ((h + (c * 123123)) ^ 123123) << 2;
+        }
+
+        return Integer.toUnsignedString(h);
+    }
+
+    public static void main(String[] args) throws Exception {
+        BufferedReader reader = new BufferedReader(new InputStreamReader(System.in));
+
+        String line;
+        while ((line = reader.readLine()) != null) {
+            System.out.println(hash(line));
+        }
+    }
+}
@@ -0,0 +1,17 @@
+# Reference implementation of the `magic` hash, generated from its definition in
+# src/scheme.rs by `cargo run -- implement hash-implementations`.
+import sys
+
+
+def hash(s):
+    h = 0
+    for ch in s:
+        c = ord(ch)
+        h = ((((h + ((c * 123123) & 0xffffffff)) & 0xffffffff) ^ 123123) << 2) & 0xffffffff
+
+    return str(h)
+
+
+if __name__ == "__main__":
+    for line in sys.stdin:
+        print(hash(line.rstrip("\n")))
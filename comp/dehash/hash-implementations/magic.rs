@@ -0,0 +1,19 @@
+// Reference implementation of the `magic` hash, generated from its definition in
+// src/scheme.rs by `cargo run -- implement hash-implementations`.
+use std::io::BufRead;
+
+fn hash(s: &str) -> String {
+    let mut h: u32 = 0;
+    for c in s.chars() {
+        let c = c as u32;
+        h = (h.wrapping_add(c.wrapping_mul(123123)) ^ 123123) << 2;
+    }
+
+    h.to_string()
+}
+
+fn main() {
+    for line in std::io::stdin().lock().lines() {
+        println!("{}", hash(&line.unwrap()));
+    }
+}
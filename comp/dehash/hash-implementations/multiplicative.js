@@ -0,0 +1,23 @@
+// Reference implementation of the `multiplicative` hash, generated from its definition in
+// src/scheme.rs by `cargo run -- implement hash-implementations`.
+
+function hash(s) {
+    let h = 0;
+    for (const ch of s) {
+        const c = ch.codePointAt(0);
+        h = ((Math.imul(h, 31) >>> 0) + c) >>> 0;
+    }
+
+    return String(h);
+}
+
+if (require.main === module) {
+    const lines = require("fs").readFileSync(0, "utf8").split("\n");
+    if (lines[lines.length - 1] === "") {
+        lines.pop();
+    }
+
+    for (const line of lines) {
+        console.log(hash(line));
+    }
+}
@@ -0,0 +1,17 @@
+# Reference implementation of the `multiplicative` hash, generated from its definition in
+# src/scheme.rs by `cargo run -- implement hash-implementations`.
+import sys
+
+
+def hash(s):
+    h = 0
+    for ch in s:
+        c = ord(ch)
+        h = (((h * 31) & 0xffffffff) + c) & 0xffffffff
+
+    return str(h)
+
+
+if __name__ == "__main__":
+    for line in sys.stdin:
+        print(hash(line.rstrip("\n")))
@@ -0,0 +1,106 @@
+//! A small language for writing down hash schemes once, so that reference
+//! implementations in other languages can be generated from the same
+//! definition.
+//!
+//! All arithmetic wraps at 32 bits.
+
+pub enum Expr {
+    /// The hash state.
+    State,
+    /// The code point of the current character.
+    Char,
+    Const(u32),
+    /// A constant written in hexadecimal.
+    Hex(u32),
+    Add(Box<Expr>, Box<Expr>),
+    Mul(Box<Expr>, Box<Expr>),
+    Xor(Box<Expr>, Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Shl(Box<Expr>, u32),
+    /// The second expression if the first is not zero, otherwise the third.
+    If(Box<Expr>, Box<Expr>, Box<Expr>),
+}
+
+#[cfg(test)]
+impl Expr {
+    pub fn eval(&self, state: u32, c: u32) -> u32 {
+        match self {
+            Expr::State => state,
+            Expr::Char => c,
+            Expr::Const(n) | Expr::Hex(n) => *n,
+            Expr::Add(a, b) => a.eval(state, c).wrapping_add(b.eval(state, c)),
+            Expr::Mul(a, b) => a.eval(state, c).wrapping_mul(b.eval(state, c)),
+            Expr::Xor(a, b) => a.eval(state, c) ^ b.eval(state, c),
+            Expr::And(a, b) => a.eval(state, c) & b.eval(state, c),
+            Expr::Shl(a, n) => a.eval(state, c) << n,
+            Expr::If(condition, then, otherwise) => {
+                if condition.eval(state, c) != 0 {
+                    then.eval(state, c)
+                } else {
+                    otherwise.eval(state, c)
+                }
+            }
+        }
+    }
+}
+
+pub fn add(a: Expr, b: Expr) -> Expr {
+    Expr::Add(Box::new(a), Box::new(b))
+}
+
+pub fn mul(a: Expr, b: Expr) -> Expr {
+    Expr::Mul(Box::new(a), Box::new(b))
+}
+
+pub fn xor(a: Expr, b: Expr) -> Expr {
+    Expr::Xor(Box::new(a), Box::new(b))
+}
+
+pub fn and(a: Expr, b: Expr) -> Expr {
+    Expr::And(Box::new(a), Box::new(b))
+}
+
+pub fn shl(a: Expr, n: u32) -> Expr {
+    Expr::Shl(Box::new(a), n)
+}
+
+pub fn if_else(condition: Expr, then: Expr, otherwise: Expr) -> Expr {
+    Expr::If(Box::new(condition), Box::new(then), Box::new(otherwise))
+}
+
+/// Assigns the expression to the state, `times` times in a row.
+pub struct Step {
+    pub times: u32,
+    pub expr: Expr,
+}
+
+pub fn once(expr: Expr) -> Step {
+    Step { times: 1, expr }
+}
+
+pub fn repeat(times: u32, expr: Expr) -> Step {
+    Step { times, expr }
+}
+
+pub struct Definition {
+    pub initial: Expr,
+    /// Applied in order for every character.
+    pub steps: Vec<Step>,
+}
+
+#[cfg(test)]
+impl Definition {
+    pub fn hash(&self, s: &str) -> String {
+        let mut state = self.initial.eval(0, 0);
+
+        for c in s.chars() {
+            for step in self.steps.iter() {
+                for _ in 0..step.times {
+                    state = step.expr.eval(state, c as u32);
+                }
+            }
+        }
+
+        state.to_string()
+    }
+}
@@ -0,0 +1,514 @@
+//! Generates the published reference implementations of each hash scheme
+//! from its `Definition`, and checks that they all agree with the judge.
+//!
+//! Every implementation reads lines from stdin and prints the hash of each.
+//! Implementations other than Python, JavaScript and Rust hash bytes rather
+//! than code points, so they only agree on ASCII passwords.
+
+use std::path::Path;
+
+use crate::{
+    definition::{Definition, Expr, Step},
+    scheme::{HashScheme, SCHEMES},
+};
+
+#[derive(Clone, Copy)]
+enum Language {
+    Python,
+    Java,
+    Cpp,
+    JavaScript,
+    Rust,
+}
+
+const LANGUAGES: [Language; 5] = [
+    Language::Python,
+    Language::Java,
+    Language::Cpp,
+    Language::JavaScript,
+    Language::Rust,
+];
+
+impl Language {
+    fn extension(self) -> &'static str {
+        match self {
+            Language::Python => "py",
+            Language::Java => "java",
+            Language::Cpp => "cpp",
+            Language::JavaScript => "js",
+            Language::Rust => "rs",
+        }
+    }
+
+    /// Renders an expression that can be used as an operand, so anything
+    /// other than a single term is wrapped in brackets.
+    fn expr(self, expr: &Expr) -> String {
+        let binary =
+            |a: &Expr, op: &str, b: &Expr| format!("({} {} {})", self.expr(a), op, self.expr(b));
+
+        match (self, expr) {
+            (_, Expr::State) => "h".to_string(),
+            (_, Expr::Char) => "c".to_string(),
+            (Language::Cpp, Expr::Const(n)) => format!("{}u", n),
+            (Language::Cpp, Expr::Hex(n)) => format!("{:#x}u", n),
+            (_, Expr::Const(n)) => n.to_string(),
+            (_, Expr::Hex(n)) => format!("{:#x}", n),
+
+            (Language::Python, Expr::Add(a, b)) => format!("({} & 0xffffffff)", binary(a, "+", b)),
+            (Language::Python, Expr::Mul(a, b)) => format!("({} & 0xffffffff)", binary(a, "*", b)),
+            (Language::Python, Expr::Shl(a, n)) => {
+                format!("(({} << {}) & 0xffffffff)", self.expr(a), n)
+            }
+
+            (Language::JavaScript, Expr::Add(a, b)) => format!("({} >>> 0)", binary(a, "+", b)),
+            (Language::JavaScript, Expr::Mul(a, b)) => format!(
+                "(Math.imul({}, {}) >>> 0)",
+                self.statement(a),
+                self.statement(b)
+            ),
+            (Language::JavaScript, Expr::Xor(a, b)) => format!("({} >>> 0)", binary(a, "^", b)),
+            (Language::JavaScript, Expr::And(a, b)) => format!("({} >>> 0)", binary(a, "&", b)),
+            (Language::JavaScript, Expr::Shl(a, n)) => {
+                format!("(({} << {}) >>> 0)", self.expr(a), n)
+            }
+
+            (Language::Rust, Expr::Add(a, b)) => {
+                format!("{}.wrapping_add({})", self.expr(a), self.statement(b))
+            }
+            (Language::Rust, Expr::Mul(a, b)) => {
+                format!("{}.wrapping_mul({})", self.expr(a), self.statement(b))
+            }
+
+            (_, Expr::Add(a, b)) => binary(a, "+", b),
+            (_, Expr::Mul(a, b)) => binary(a, "*", b),
+            (_, Expr::Xor(a, b)) => binary(a, "^", b),
+            (_, Expr::And(a, b)) => binary(a, "&", b),
+            (_, Expr::Shl(a, n)) => format!("({} << {})", self.expr(a), n),
+
+            (Language::Python, Expr::If(condition, then, otherwise)) => format!(
+                "({} if {} != 0 else {})",
+                self.expr(then),
+                self.expr(condition),
+                self.expr(otherwise)
+            ),
+            (Language::Rust, Expr::If(condition, then, otherwise)) => format!(
+                "(if {} != 0 {{ {} }} else {{ {} }})",
+                self.expr(condition),
+                self.statement(then),
+                self.statement(otherwise)
+            ),
+            (Language::JavaScript, Expr::If(condition, then, otherwise)) => format!(
+                "({} !== 0 ? {} : {})",
+                self.expr(condition),
+                self.expr(then),
+                self.expr(otherwise)
+            ),
+            (_, Expr::If(condition, then, otherwise)) => format!(
+                "({} != 0 ? {} : {})",
+                self.expr(condition),
+                self.expr(then),
+                self.expr(otherwise)
+            ),
+        }
+    }
+
+    /// Renders an expression without brackets around the whole thing.
+    fn statement(self, expr: &Expr) -> String {
+        let rendered = self.expr(expr);
+
+        match rendered.strip_prefix('(').and_then(|r| r.strip_suffix(')')) {
+            Some(inner) if brackets_balance(inner) => inner.to_string(),
+            _ => rendered,
+        }
+    }
+
+    fn steps(self, steps: &[Step], indent: &str) -> String {
+        let mut lines = Vec::new();
+
+        for step in steps {
+            let assign = match self {
+                Language::Python => format!("h = {}", self.statement(&step.expr)),
+                _ => format!("h = {};", self.statement(&step.expr)),
+            };
+
+            if step.times == 1 {
+                lines.push(format!("{}{}", indent, assign));
+                continue;
+            }
+
+            let header = match self {
+                Language::Python => format!("for _ in range({}):", step.times),
+                Language::Java | Language::Cpp => {
+                    format!("for (int i = 0; i < {}; i++) {{", step.times)
+                }
+                Language::JavaScript => format!("for (let i = 0; i < {}; i++) {{", step.times),
+                Language::Rust => format!("for _ in 0..{} {{", step.times),
+            };
+
+            lines.push(format!("{}{}", indent, header));
+            lines.push(format!("{}    {}", indent, assign));
+
+            if !matches!(self, Language::Python) {
+                lines.push(format!("{}}}", indent));
+            }
+        }
+
+        lines.join("\n")
+    }
+
+    fn program(self, scheme: &dyn HashScheme) -> String {
+        let Definition { initial, steps } = scheme.definition();
+
+        let initial = self.statement(&initial);
+        let steps = match self {
+            Language::Java => self.steps(&steps, "            "),
+            _ => self.steps(&steps, "        "),
+        };
+
+        let header = format!(
+            "Reference implementation of the `{}` hash, generated from its definition in\nsrc/scheme.rs by `cargo run -- implement hash-implementations`.",
+            scheme.name()
+        );
+
+        match self {
+            Language::Python => format!(
+                r#"{comment}
+import sys
+
+
+def hash(s):
+    h = {initial}
+    for ch in s:
+        c = ord(ch)
+{steps}
+
+    return str(h)
+
+
+if __name__ == "__main__":
+    for line in sys.stdin:
+        print(hash(line.rstrip("\n")))
+"#,
+                comment = comment(&header, "# "),
+            ),
+            Language::Java => format!(
+                r#"{comment}
+import java.io.BufferedReader;
+import java.io.InputStreamReader;
+
+class {class} {{
+    static String hash(String s) {{
+        int h = {initial};
+        for (int c : s.getBytes()) {{
+            c &= 0xff;
+{steps}
+        }}
+
+        return Integer.toUnsignedString(h);
+    }}
+
+    public static void main(String[] args) throws Exception {{
+        BufferedReader reader = new BufferedReader(new InputStreamReader(System.in));
+
+        String line;
+        while ((line = reader.readLine()) != null) {{
+            System.out.println(hash(line));
+        }}
+    }}
+}}
+"#,
+                comment = comment(&header, "// "),
+                class = class_name(scheme.name()),
+            ),
+            Language::Cpp => format!(
+                r#"{comment}
+#include <cstdint>
+#include <iostream>
+#include <string>
+
+std::string hash(const std::string &s) {{
+    uint32_t h = {initial};
+    for (unsigned char ch : s) {{
+        uint32_t c = ch;
+{steps}
+    }}
+
+    return std::to_string(h);
+}}
+
+int main() {{
+    std::string line;
+    while (std::getline(std::cin, line)) {{
+        std::cout << hash(line) << "\n";
+    }}
+}}
+"#,
+                comment = comment(&header, "// "),
+            ),
+            Language::JavaScript => format!(
+                r#"{comment}
+
+function hash(s) {{
+    let h = {initial};
+    for (const ch of s) {{
+        const c = ch.codePointAt(0);
+{steps}
+    }}
+
+    return String(h);
+}}
+
+if (require.main === module) {{
+    const lines = require("fs").readFileSync(0, "utf8").split("\n");
+    if (lines[lines.length - 1] === "") {{
+        lines.pop();
+    }}
+
+    for (const line of lines) {{
+        console.log(hash(line));
+    }}
+}}
+"#,
+                comment = comment(&header, "// "),
+            ),
+            Language::Rust => format!(
+                r#"{comment}
+use std::io::BufRead;
+
+fn hash(s: &str) -> String {{
+    let mut h: u32 = {initial};
+    for c in s.chars() {{
+        let c = c as u32;
+{steps}
+    }}
+
+    h.to_string()
+}}
+
+fn main() {{
+    for line in std::io::stdin().lock().lines() {{
+        println!("{{}}", hash(&line.unwrap()));
+    }}
+}}
+"#,
+                comment = comment(&header, "// "),
+            ),
+        }
+    }
+}
+
+fn brackets_balance(s: &str) -> bool {
+    let mut depth = 0;
+
+    for c in s.chars() {
+        match c {
+            '(' => depth += 1,
+            ')' if depth == 0 => return false,
+            ')' => depth -= 1,
+            _ => {}
+        }
+    }
+
+    depth == 0
+}
+
+fn comment(text: &str, prefix: &str) -> String {
+    text.lines()
+        .map(|line| format!("{}{}", prefix, line))
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+fn class_name(name: &str) -> String {
+    let mut chars = name.chars();
+    chars.next().unwrap().to_uppercase().chain(chars).collect()
+}
+
+fn path(dir: &Path, scheme: &dyn HashScheme, language: Language) -> std::path::PathBuf {
+    dir.join(format!("{}.{}", scheme.name(), language.extension()))
+}
+
+/// Writes every scheme in every language to `dir`.
+pub fn write_implementations(dir: &Path) {
+    std::fs::create_dir_all(dir).unwrap();
+
+    for scheme in SCHEMES {
+        for language in LANGUAGES {
+            std::fs::write(path(dir, scheme, language), language.program(scheme)).unwrap();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::Write,
+        process::{Command, Stdio},
+    };
+
+    use rand::SeedableRng;
+    use rand_chacha::ChaChaRng;
+
+    use super::*;
+
+    impl Language {
+        fn name(self) -> &'static str {
+            match self {
+                Language::Python => "Python",
+                Language::Java => "Java",
+                Language::Cpp => "C++",
+                Language::JavaScript => "JavaScript",
+                Language::Rust => "Rust",
+            }
+        }
+
+        /// Runs the implementation at `path` on the samples, one per line.
+        fn run(self, path: &Path, samples: &[String]) -> Result<Vec<String>, String> {
+            let build = std::env::temp_dir().join(format!(
+                "dehash-{}-{}",
+                std::process::id(),
+                path.file_stem().unwrap().to_string_lossy()
+            ));
+
+            let compile = match self {
+                Language::Cpp => Some(vec!["g++", "-O2", "-o"]),
+                Language::Rust => Some(vec!["rustc", "-O", "-o"]),
+                _ => None,
+            };
+
+            if let Some(compile) = compile {
+                let status = Command::new(compile[0])
+                    .args(&compile[1..])
+                    .arg(&build)
+                    .arg(path)
+                    .status()
+                    .map_err(|e| format!("Could not run `{}`: {}", compile[0], e))?;
+
+                if !status.success() {
+                    return Err(format!(
+                        "`{}` failed to compile {}.",
+                        compile[0],
+                        path.display()
+                    ));
+                }
+            }
+
+            let mut command = match self {
+                Language::Python => Command::new("python3"),
+                Language::Java => Command::new("java"),
+                Language::JavaScript => Command::new("node"),
+                Language::Cpp | Language::Rust => Command::new(&build),
+            };
+
+            if let Language::Python | Language::Java | Language::JavaScript = self {
+                command.arg(path);
+            }
+
+            let mut child = command
+                .stdin(Stdio::piped())
+                .stdout(Stdio::piped())
+                .spawn()
+                .map_err(|e| format!("Could not run the {} implementation: {}", self.name(), e))?;
+
+            let mut input = samples.join("\n");
+            input.push('\n');
+            child
+                .stdin
+                .take()
+                .unwrap()
+                .write_all(input.as_bytes())
+                .unwrap();
+
+            let output = child.wait_with_output().unwrap();
+            let _ = std::fs::remove_file(&build);
+
+            if !output.status.success() {
+                return Err(format!("The {} implementation failed.", self.name()));
+            }
+
+            Ok(String::from_utf8_lossy(&output.stdout)
+                .lines()
+                .map(str::to_string)
+                .collect())
+        }
+    }
+
+    /// Hashes sample passwords with the judge's implementation of each
+    /// scheme, its definition and every implementation in
+    /// `hash-implementations/`, which must be up to date. Languages whose
+    /// tools aren't installed are skipped.
+    #[test]
+    fn implementations_agree() {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("hash-implementations");
+
+        let mut samples = [
+            "",
+            "a",
+            "password",
+            "Lorem ipsum dolor sit amet",
+            " !\"#$%&'()*+,-./0123456789:;<=>?@[\\]^_`{|}~",
+        ]
+        .map(str::to_string)
+        .to_vec();
+
+        let mut rng = ChaChaRng::seed_from_u64(0);
+        samples.extend((0..100).map(|_| crate::random_string(&mut rng)));
+
+        let mut problems = vec![];
+
+        for scheme in SCHEMES {
+            let expected = samples
+                .iter()
+                .map(|sample| scheme.hash(sample))
+                .collect::<Vec<String>>();
+
+            let definition = scheme.definition();
+            let mut results = vec![(
+                "definition".to_string(),
+                samples
+                    .iter()
+                    .map(|sample| definition.hash(sample))
+                    .collect(),
+            )];
+
+            for language in LANGUAGES {
+                let file = path(&dir, scheme, language);
+
+                if std::fs::read_to_string(&file).ok() != Some(language.program(scheme)) {
+                    problems.push(format!(
+                        "{} is missing or out of date, regenerate it with `cargo run -- implement hash-implementations`.",
+                        file.display()
+                    ));
+                    continue;
+                }
+
+                match language.run(&file, &samples) {
+                    Ok(hashes) => results.push((language.name().to_string(), hashes)),
+                    Err(e) => eprintln!(
+                        "Skipping {} for `{}`: {}",
+                        language.name(),
+                        scheme.name(),
+                        e
+                    ),
+                }
+            }
+
+            for (name, hashes) in results {
+                let mismatch = (0..samples.len()).find(|&i| hashes.get(i) != Some(&expected[i]));
+
+                if let Some(i) = mismatch {
+                    problems.push(format!(
+                        "`{}` {} hashes {:?} to {:?} but the judge hashes it to {}.",
+                        scheme.name(),
+                        name,
+                        samples[i],
+                        hashes.get(i),
+                        expected[i]
+                    ));
+                }
+            }
+        }
+
+        assert!(problems.is_empty(), "{}", problems.join("\n"));
+    }
+}
@@ -1,9 +1,23 @@
-use std::{collections::hash_map::DefaultHasher, hash::{Hash, Hasher}, process::exit};
+use std::{
+    collections::hash_map::DefaultHasher,
+    hash::{Hash, Hasher},
+    path::Path,
+    process::exit,
+};
 
-use rand::{Rng, SeedableRng};
+use rand::{seq::SliceRandom, Rng, SeedableRng};
 
 use rand_chacha::ChaChaRng;
 
+use implement::write_implementations;
+use scheme::{Crc16, Fnv1a, HashScheme, Magic, Multiplicative};
+use solve::{shortest_preimage, Search};
+
+mod definition;
+mod implement;
+mod scheme;
+mod solve;
+
 fn main() {
     let args = std::env::args().collect::<Vec<String>>();
 
@@ -13,61 +27,207 @@ fn main() {
     args[2].hash(&mut s);
     let seed = s.finish();
 
-    let our_hash = hash(random_string(seed));
+    let problem = generate_problem(seed, Difficulty::from_env());
 
     match args[1].as_str() {
         "generate" => {
-            println!("{}", our_hash);
+            println!("{} {}", problem.scheme.name(), problem.prefix_length);
+            println!("{}", problem.hash);
         }
         "validate" => {
             let mut buffer = String::new();
             std::io::stdin().read_line(&mut buffer).unwrap();
 
-            let user_hash = hash(buffer.trim().to_string());
+            // Only the line ending is removed, spaces are part of the password.
+            let password = buffer.trim_end_matches(['\n', '\r']);
+
+            if let Err(e) = check_password(password, reject_lorem()) {
+                eprintln!("{}", e);
+                exit(1);
+            }
+
+            let user_hash = problem.scheme.hash(password);
 
-            if compare_head(our_hash, user_hash) {
+            if hash_matches(&problem.hash, &user_hash, problem.prefix_length) {
                 exit(0);
             } else {
                 exit(1);
             }
         }
+        "solve" => {
+            // Prints a shortest password, and how many passwords were hashed
+            // to find it, for organisers to gauge the difficulty.
+            let Search {
+                password,
+                candidates,
+            } = problem.solution;
+            let password = password.unwrap();
+
+            println!("{}", password);
+            eprintln!(
+                "Found a {} character password after hashing {} passwords.",
+                password.len(),
+                candidates
+            );
+        }
+        "implement" => {
+            // The seed argument is the directory to write to.
+            write_implementations(Path::new(&args[2]));
+        }
         _ => panic!(),
     }
 }
 
-fn hash(s: String) -> String {
-    const MAGIC: i128 = 123123;
+/// Selected with the `DEHASH_DIFFICULTY` environment variable.
+#[derive(Clone, Copy)]
+enum Difficulty {
+    /// The original magic hash, matching 3 characters.
+    Easy,
+    Medium,
+    Hard,
+}
+
+impl Difficulty {
+    fn from_env() -> Difficulty {
+        match std::env::var("DEHASH_DIFFICULTY").as_deref() {
+            Err(_) | Ok("easy") => Difficulty::Easy,
+            Ok("medium") => Difficulty::Medium,
+            Ok("hard") => Difficulty::Hard,
+            Ok(other) => panic!("Unknown dehash difficulty: {}", other),
+        }
+    }
 
-    let mut h: i128 = 0;
+    /// The schemes each seed picks from.
+    fn schemes(self) -> &'static [&'static dyn HashScheme] {
+        match self {
+            Difficulty::Easy => &[&Magic],
+            Difficulty::Medium => &[&Magic, &Multiplicative, &Fnv1a],
+            Difficulty::Hard => &[&Multiplicative, &Fnv1a, &Crc16],
+        }
+    }
 
-    for c in s.chars() {
-        h += c as i128 * MAGIC;
-        h ^= MAGIC;
-        h <<= 2;
-        h %= 1 << 32;
+    /// How many leading characters of the hash have to match.
+    fn prefix_length(self) -> usize {
+        match self {
+            Difficulty::Easy => 3,
+            Difficulty::Medium => 4,
+            Difficulty::Hard => 6,
+        }
     }
+}
 
-    return h.to_string();
+struct Problem {
+    scheme: &'static dyn HashScheme,
+    prefix_length: usize,
+    hash: String,
+    /// A shortest password teams could submit.
+    solution: Search,
 }
 
-static LOREM_IPSUM: &str = "Lorem ipsum dolor sit amet, officia excepteur ex fugiat reprehenderit enim labore culpa sint ad nisi Lorem pariatur mollit ex esse exercitation amet. Nisi anim cupidatat excepteur officia. Reprehenderit nostrud nostrud ipsum Lorem est aliquip amet voluptate voluptate dolor minim nulla est proident. Nostrud officia pariatur ut officia. Sit irure elit esse ea nulla sunt ex occaecat reprehenderit commodo officia dolor Lorem duis laboris cupidatat officia voluptate. Culpa proident adipisicing id nulla nisi laboris ex in Lorem sunt duis officia eiusmod. Aliqua reprehenderit commodo ex non excepteur duis sunt velit enim. Voluptate laboris sint cupidatat ullamco ut ea consectetur et est culpa et culpa duis.";
+/// Every problem has a password at most this long, otherwise the seed is
+/// rejected. Searching one character further takes 95 times longer.
+const MAX_SOLUTION_LENGTH: usize = 4;
 
-fn random_string(seed: u64) -> String {
+fn generate_problem(seed: u64, difficulty: Difficulty) -> Problem {
     let mut rng = ChaChaRng::seed_from_u64(seed);
 
-    let length = rng.gen_range(40..60);
+    let password = random_string(&mut rng);
+
+    let schemes = difficulty.schemes();
+    let scheme = match schemes {
+        [scheme] => *scheme,
+        _ => *schemes.choose(&mut rng).unwrap(),
+    };
+
+    let hash = scheme.hash(&password);
+
+    // Short hashes (CRC-16 is at most 5 digits) have to match in full.
+    let prefix_length = difficulty.prefix_length().min(hash.len());
+
+    let solution = shortest_preimage(scheme, &hash, prefix_length, MAX_SOLUTION_LENGTH);
+
+    if solution.password.is_none() {
+        return generate_problem(seed.wrapping_add(1), difficulty);
+    }
+
+    Problem {
+        scheme,
+        prefix_length,
+        hash,
+        solution,
+    }
+}
+
+/// The longest password accepted by the judge.
+const MAX_PASSWORD_LENGTH: usize = 64;
+
+/// The original passwords are at least this long.
+const MIN_ORIGINAL_LENGTH: usize = 40;
+
+/// Whether passwords copied from the text the original passwords are taken
+/// from are rejected, set with `DEHASH_REJECT_LOREM=1`.
+fn reject_lorem() -> bool {
+    std::env::var("DEHASH_REJECT_LOREM").as_deref() == Ok("1")
+}
+
+/// Checks the constraints on passwords given in the problem, explaining the
+/// first one that's broken.
+fn check_password(password: &str, reject_lorem: bool) -> Result<(), String> {
+    if password.is_empty() {
+        return Err("The password is empty.".to_string());
+    }
+
+    if let Some((i, c)) = password
+        .chars()
+        .enumerate()
+        .find(|(_, c)| !(' '..='~').contains(c))
+    {
+        return Err(format!(
+            "Character {} of the password is {:?}, which is not printable ASCII.",
+            i + 1,
+            c
+        ));
+    }
+
+    if password.len() > MAX_PASSWORD_LENGTH {
+        return Err(format!(
+            "The password is {} characters long, but can be at most {}.",
+            password.len(),
+            MAX_PASSWORD_LENGTH
+        ));
+    }
+
+    if reject_lorem && password.len() >= MIN_ORIGINAL_LENGTH && LOREM_IPSUM.contains(password) {
+        return Err("The password is copied from the original text.".to_string());
+    }
+
+    Ok(())
+}
+
+static LOREM_IPSUM: &str = "Lorem ipsum dolor sit amet, officia excepteur ex fugiat reprehenderit enim labore culpa sint ad nisi Lorem pariatur mollit ex esse exercitation amet. Nisi anim cupidatat excepteur officia. Reprehenderit nostrud nostrud ipsum Lorem est aliquip amet voluptate voluptate dolor minim nulla est proident. Nostrud officia pariatur ut officia. Sit irure elit esse ea nulla sunt ex occaecat reprehenderit commodo officia dolor Lorem duis laboris cupidatat officia voluptate. Culpa proident adipisicing id nulla nisi laboris ex in Lorem sunt duis officia eiusmod. Aliqua reprehenderit commodo ex non excepteur duis sunt velit enim. Voluptate laboris sint cupidatat ullamco ut ea consectetur et est culpa et culpa duis.";
+
+fn random_string<R: Rng>(rng: &mut R) -> String {
+    let length = rng.gen_range(MIN_ORIGINAL_LENGTH..60);
     let start = rng.gen_range(0..LOREM_IPSUM.len() - length);
 
-    return LOREM_IPSUM[start..start + length].to_string();
+    LOREM_IPSUM[start..start + length].to_string()
 }
 
-fn compare_head(a: String, b: String) -> bool {
-    const LENGTH: usize = 3;
+/// Whether the first `length` characters of the two hashes are the same. A
+/// hash no longer than that has to be matched in full, so a longer hash that
+/// merely starts with it doesn't count.
+fn hash_matches(hash: &str, user_hash: &str, length: usize) -> bool {
+    if hash.len() <= length {
+        return user_hash == hash;
+    }
 
-    if a.len() < LENGTH || b.len() < LENGTH {
+    compare_head(hash, user_hash, length)
+}
+
+fn compare_head(a: &str, b: &str, length: usize) -> bool {
+    if a.len() < length || b.len() < length {
         return false;
     }
 
-    return a.chars().take(LENGTH).eq(b.chars().take(LENGTH));
+    a.chars().take(length).eq(b.chars().take(length))
 }
-
@@ -0,0 +1,237 @@
+//! The weak hash functions passwords can be hashed with.
+//!
+//! Every scheme keeps a 32-bit state that is updated with the code point of
+//! each character in turn. The hash is the final state in decimal.
+//!
+//! Each scheme is also written down as a `Definition`, which the reference
+//! implementations are generated from. The tests in `implement` make sure
+//! the two agree.
+
+use crate::definition::{
+    add, and, if_else, mul, once, repeat, shl, xor, Definition,
+    Expr::{Char, Const, Hex, State},
+};
+
+pub const SCHEMES: [&dyn HashScheme; 4] = [&Magic, &Fnv1a, &Crc16, &Multiplicative];
+
+pub trait HashScheme {
+    /// Published in the input so teams know which function to attack.
+    fn name(&self) -> &'static str;
+
+    fn initial(&self) -> u32;
+
+    fn update(&self, state: u32, c: u32) -> u32;
+
+    /// Every state that `update` takes to `state` with the character `c`.
+    fn preimages(&self, state: u32, c: u32) -> Vec<u32>;
+
+    fn definition(&self) -> Definition;
+
+    fn hash(&self, s: &str) -> String {
+        s.chars()
+            .fold(self.initial(), |state, c| self.update(state, c as u32))
+            .to_string()
+    }
+}
+
+/// The original shift and xor hash with a magic constant.
+pub struct Magic;
+
+const MAGIC: u64 = 123123;
+
+impl HashScheme for Magic {
+    fn name(&self) -> &'static str {
+        "magic"
+    }
+
+    fn initial(&self) -> u32 {
+        0
+    }
+
+    fn update(&self, state: u32, c: u32) -> u32 {
+        (((state as u64 + c as u64 * MAGIC) ^ MAGIC) << 2) as u32
+    }
+
+    fn preimages(&self, state: u32, c: u32) -> Vec<u32> {
+        if state & 3 != 0 {
+            return Vec::new();
+        }
+
+        // The shift drops the top bits of a sum below 2^33 + c * MAGIC.
+        (0..=8u64)
+            .map(|top| (state as u64 >> 2) + (top << 30))
+            .filter_map(|sum| (sum ^ MAGIC).checked_sub(c as u64 * MAGIC))
+            .filter_map(|previous| u32::try_from(previous).ok())
+            .filter(|&previous| self.update(previous, c) == state)
+            .collect()
+    }
+
+    fn definition(&self) -> Definition {
+        Definition {
+            initial: Const(0),
+            steps: vec![once(shl(
+                xor(add(State, mul(Char, Const(123123))), Const(123123)),
+                2,
+            ))],
+        }
+    }
+}
+
+/// 32-bit FNV-1a.
+pub struct Fnv1a;
+
+impl HashScheme for Fnv1a {
+    fn name(&self) -> &'static str {
+        "fnv1a"
+    }
+
+    fn initial(&self) -> u32 {
+        0x811c9dc5
+    }
+
+    fn update(&self, state: u32, c: u32) -> u32 {
+        (state ^ c).wrapping_mul(0x01000193)
+    }
+
+    fn preimages(&self, state: u32, c: u32) -> Vec<u32> {
+        // The multiplicative inverse of 0x01000193.
+        vec![state.wrapping_mul(0x359c449b) ^ c]
+    }
+
+    fn definition(&self) -> Definition {
+        Definition {
+            initial: Hex(0x811c9dc5),
+            steps: vec![once(mul(xor(State, Char), Hex(0x01000193)))],
+        }
+    }
+}
+
+/// CRC-16/CCITT-FALSE over the low byte of each character.
+pub struct Crc16;
+
+impl HashScheme for Crc16 {
+    fn name(&self) -> &'static str {
+        "crc16"
+    }
+
+    fn initial(&self) -> u32 {
+        0xffff
+    }
+
+    fn update(&self, state: u32, c: u32) -> u32 {
+        let mut crc = state ^ ((c & 0xff) << 8);
+
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 {
+                (crc << 1) ^ 0x1021
+            } else {
+                crc << 1
+            };
+        }
+
+        crc & 0xffff
+    }
+
+    fn preimages(&self, state: u32, c: u32) -> Vec<u32> {
+        if state > 0xffff {
+            return Vec::new();
+        }
+
+        // The polynomial is odd, so the low bit says whether it was xored in.
+        let mut crc = state;
+
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 {
+                ((crc ^ 0x1021) >> 1) | 0x8000
+            } else {
+                crc >> 1
+            };
+        }
+
+        vec![crc ^ ((c & 0xff) << 8)]
+    }
+
+    fn definition(&self) -> Definition {
+        Definition {
+            initial: Hex(0xffff),
+            steps: vec![
+                once(xor(State, shl(and(Char, Hex(0xff)), 8))),
+                repeat(
+                    8,
+                    if_else(
+                        and(State, Hex(0x8000)),
+                        xor(shl(State, 1), Hex(0x1021)),
+                        shl(State, 1),
+                    ),
+                ),
+                once(and(State, Hex(0xffff))),
+            ],
+        }
+    }
+}
+
+/// Multiply by 31 and add, like Java's `String.hashCode` but unsigned.
+pub struct Multiplicative;
+
+impl HashScheme for Multiplicative {
+    fn name(&self) -> &'static str {
+        "multiplicative"
+    }
+
+    fn initial(&self) -> u32 {
+        0
+    }
+
+    fn update(&self, state: u32, c: u32) -> u32 {
+        state.wrapping_mul(31).wrapping_add(c)
+    }
+
+    fn preimages(&self, state: u32, c: u32) -> Vec<u32> {
+        // The multiplicative inverse of 31.
+        vec![state.wrapping_sub(c).wrapping_mul(0xbdef7bdf)]
+    }
+
+    fn definition(&self) -> Definition {
+        Definition {
+            initial: Const(0),
+            steps: vec![once(add(mul(State, Const(31)), Char))],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaChaRng;
+
+    use super::*;
+
+    #[test]
+    fn preimages_undo_update() {
+        let mut rng = ChaChaRng::seed_from_u64(0);
+
+        for scheme in SCHEMES {
+            for _ in 0..10000 {
+                let state = match scheme.name() {
+                    "crc16" => rng.gen_range(0..=0xffff),
+                    _ => rng.gen(),
+                };
+                let c = rng.gen_range(b' '..=b'~') as u32;
+                let next = scheme.update(state, c);
+
+                let preimages = scheme.preimages(next, c);
+
+                assert!(
+                    preimages.contains(&state),
+                    "{} {} {}",
+                    scheme.name(),
+                    state,
+                    c
+                );
+                for previous in preimages {
+                    assert_eq!(scheme.update(previous, c), next, "{}", scheme.name());
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,208 @@
+//! Finds a shortest password whose hash starts with the right characters, to
+//! prove every problem can be solved and to measure how much searching it
+//! takes.
+
+use std::{collections::HashMap, ops::RangeInclusive};
+
+use crate::scheme::HashScheme;
+
+/// The characters passwords can use, space to `~`.
+const PRINTABLE: RangeInclusive<u8> = b' '..=b'~';
+
+const PRINTABLE_COUNT: usize = 95;
+
+pub struct Search {
+    /// The first shortest password, in ASCII order.
+    pub password: Option<String>,
+    /// How many passwords trying every one in order would hash, up to and
+    /// including the one found.
+    pub candidates: u64,
+}
+
+/// Tries every printable password of each length in turn, up to
+/// `max_length`. The states of every password of one length are kept in
+/// ASCII order, so each password of the next length only costs one update.
+///
+/// The last length would cost as much as all the others put together, so
+/// when there are fewer accepted states than passwords one shorter, it works
+/// back from each accepted state through every last character instead, and
+/// looks the state before it up among the shorter passwords.
+pub fn shortest_preimage(
+    scheme: &dyn HashScheme,
+    hash: &str,
+    prefix_length: usize,
+    max_length: usize,
+) -> Search {
+    let accepted = accepted_states(hash, prefix_length);
+    let accepted_count = accepted
+        .iter()
+        .map(|range| range.end() - range.start() + 1)
+        .sum::<u64>();
+    let is_accepted = |state: &u32| {
+        accepted
+            .iter()
+            .any(|range| range.contains(&(*state as u64)))
+    };
+
+    let mut search = Search {
+        password: None,
+        candidates: 0,
+    };
+
+    // The state of the password written as the index in base 95.
+    let mut states = vec![scheme.initial()];
+
+    for length in 1..=max_length {
+        let found = if length < max_length {
+            states = successors(scheme, &states).collect();
+            states.iter().position(is_accepted)
+        } else if accepted_count < states.len() as u64 {
+            last_by_preimages(scheme, &states, &accepted)
+        } else {
+            successors(scheme, &states).position(|state| is_accepted(&state))
+        };
+
+        if let Some(index) = found {
+            search.candidates += index as u64 + 1;
+            search.password = Some(password(index, length));
+            break;
+        }
+
+        search.candidates += PRINTABLE_COUNT.pow(length as u32) as u64;
+    }
+
+    search
+}
+
+/// The states after adding each character to each state in turn.
+fn successors<'a>(scheme: &'a dyn HashScheme, states: &'a [u32]) -> impl Iterator<Item = u32> + 'a {
+    states
+        .iter()
+        .flat_map(move |&state| PRINTABLE.map(move |c| scheme.update(state, c as u32)))
+}
+
+/// The index of the first password one character longer than those with
+/// `states` that ends in an accepted state.
+fn last_by_preimages(
+    scheme: &dyn HashScheme,
+    states: &[u32],
+    accepted: &[RangeInclusive<u64>],
+) -> Option<usize> {
+    let mut first_with_state = HashMap::new();
+
+    for (index, &state) in states.iter().enumerate() {
+        first_with_state.entry(state).or_insert(index);
+    }
+
+    accepted
+        .iter()
+        .cloned()
+        .flatten()
+        .flat_map(|state| {
+            PRINTABLE.flat_map(move |c| {
+                scheme
+                    .preimages(state as u32, c as u32)
+                    .into_iter()
+                    .map(move |previous| (previous, c))
+            })
+        })
+        .filter_map(|(previous, c)| {
+            let index = first_with_state.get(&previous)?;
+            Some(index * PRINTABLE_COUNT + (c - PRINTABLE.start()) as usize)
+        })
+        .min()
+}
+
+/// The password of the given length written as `index` in base 95.
+fn password(mut index: usize, length: usize) -> String {
+    let mut password = vec![*PRINTABLE.start(); length];
+
+    for c in password.iter_mut().rev() {
+        *c += (index % PRINTABLE_COUNT) as u8;
+        index /= PRINTABLE_COUNT;
+    }
+
+    String::from_utf8(password).unwrap()
+}
+
+/// The states whose decimal form starts with the first `prefix_length`
+/// characters of the hash, as one range for each possible number of digits.
+/// A hash no longer than that only accepts itself.
+fn accepted_states(hash: &str, prefix_length: usize) -> Vec<RangeInclusive<u64>> {
+    if prefix_length >= hash.len() {
+        let state = hash.parse::<u64>().unwrap();
+        return vec![state..=state];
+    }
+
+    let prefix = &hash[..prefix_length];
+
+    // Only zero itself is written with a leading zero.
+    if prefix.starts_with('0') {
+        return vec![0..=0];
+    }
+
+    let prefix = prefix.parse::<u64>().unwrap();
+    let max_digits = (u32::MAX as u64).to_string().len();
+
+    (prefix_length..=max_digits)
+        .map(|digits| {
+            let scale = 10u64.pow((digits - prefix_length) as u32);
+            prefix * scale..=((prefix + 1) * scale - 1).min(u32::MAX as u64)
+        })
+        .filter(|range| !range.is_empty())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaChaRng;
+
+    use super::*;
+    use crate::{random_string, scheme::SCHEMES};
+
+    /// Hashes every password up to `max_length` in ASCII order.
+    fn brute_force(
+        scheme: &dyn HashScheme,
+        hash: &str,
+        prefix_length: usize,
+        max_length: usize,
+    ) -> Option<String> {
+        let accepted = accepted_states(hash, prefix_length);
+
+        (1..=max_length).find_map(|length| {
+            (0..PRINTABLE_COUNT.pow(length as u32))
+                .map(|index| password(index, length))
+                .find(|password| {
+                    let state = scheme.hash(password).parse::<u64>().unwrap();
+                    accepted.iter().any(|range| range.contains(&state))
+                })
+        })
+    }
+
+    #[test]
+    fn finds_first_shortest_password() {
+        let mut rng = ChaChaRng::seed_from_u64(0);
+
+        for scheme in SCHEMES {
+            for _ in 0..3 {
+                let hash = scheme.hash(&random_string(&mut rng));
+                let search = shortest_preimage(scheme, &hash, 3, 3);
+
+                assert_eq!(search.password, brute_force(scheme, &hash, 3, 3));
+
+                // Matching a short password's hash in full searches back from
+                // the last character.
+                let length = rng.gen_range(1..=3);
+                let short = (0..length)
+                    .map(|_| rng.gen_range(PRINTABLE) as char)
+                    .collect::<String>();
+                let hash = scheme.hash(&short);
+                let search = shortest_preimage(scheme, &hash, hash.len(), 3);
+
+                assert!(search.password.is_some());
+                assert_eq!(search.password, brute_force(scheme, &hash, hash.len(), 3));
+            }
+        }
+    }
+}
@@ -0,0 +1,116 @@
+use crate::NEUCLEOTIDES;
+
+/// Scores for global alignment. Substitutions are indexed in the same order as
+/// NEUCLEOTIDES and every gap costs the same.
+pub struct Scoring {
+    pub substitution: [[i64; 4]; 4],
+    pub gap: i64,
+}
+
+/// Matches score 2, transitions (A <-> G, C <-> T) are penalised less than
+/// transversions, and gaps cost the most.
+pub const SCORING: Scoring = Scoring {
+    substitution: [
+        [2, -2, -1, -2],
+        [-2, 2, -2, -1],
+        [-1, -2, 2, -2],
+        [-2, -1, -2, 2],
+    ],
+    gap: -3,
+};
+
+impl Scoring {
+    fn score(&self, a: char, b: char) -> i64 {
+        self.substitution[index(a)][index(b)]
+    }
+
+    /// Prints the matrix as four rows (A, C, G, T) followed by the gap score.
+    pub fn print(&self) {
+        for row in &self.substitution {
+            println!(
+                "{}",
+                row.iter()
+                    .map(|score| score.to_string())
+                    .collect::<Vec<String>>()
+                    .join(" ")
+            );
+        }
+        println!("{}", self.gap);
+    }
+}
+
+fn index(nucleotide: char) -> usize {
+    NEUCLEOTIDES
+        .iter()
+        .position(|&n| n == nucleotide)
+        .expect("Not a nucleotide.")
+}
+
+/// Needleman-Wunsch: the best score of any global alignment of the sequences.
+pub fn best_alignment_score(seq1: &str, seq2: &str, scoring: &Scoring) -> i64 {
+    let seq1 = seq1.chars().collect::<Vec<char>>();
+    let seq2 = seq2.chars().collect::<Vec<char>>();
+
+    // Only the previous row is needed at any time.
+    let mut previous = (0..=seq2.len() as i64)
+        .map(|j| j * scoring.gap)
+        .collect::<Vec<i64>>();
+
+    for (i, &a) in seq1.iter().enumerate() {
+        let mut row = vec![(i as i64 + 1) * scoring.gap];
+
+        for (j, &b) in seq2.iter().enumerate() {
+            let diagonal = previous[j] + scoring.score(a, b);
+            let up = previous[j + 1] + scoring.gap;
+            let left = row[j] + scoring.gap;
+
+            row.push(diagonal.max(up).max(left));
+        }
+
+        previous = row;
+    }
+
+    previous[seq2.len()]
+}
+
+/// Scores a submitted alignment: two rows of equal length using `-` for gaps,
+/// which must spell out the original sequences once the gaps are removed.
+pub fn alignment_score(
+    row1: &str,
+    row2: &str,
+    seq1: &str,
+    seq2: &str,
+    scoring: &Scoring,
+) -> Result<i64, String> {
+    if row1.len() != row2.len() {
+        return Err("Both rows of the alignment must be the same length.".to_string());
+    }
+
+    for row in [row1, row2] {
+        if let Some(c) = row.chars().find(|c| *c != '-' && !NEUCLEOTIDES.contains(c)) {
+            return Err(format!("Invalid character `{}` in alignment.", c));
+        }
+    }
+
+    if row1.replace('-', "") != seq1 {
+        return Err("The first row is not the first sequence with gaps.".to_string());
+    }
+
+    if row2.replace('-', "") != seq2 {
+        return Err("The second row is not the second sequence with gaps.".to_string());
+    }
+
+    let mut score = 0;
+
+    for (column, (a, b)) in row1.chars().zip(row2.chars()).enumerate() {
+        score += match (a, b) {
+            ('-', '-') => {
+                return Err(format!("Column {} is a gap in both rows.", column + 1));
+            }
+            ('-', _) | (_, '-') => scoring.gap,
+            (a, b) => scoring.score(a, b),
+        };
+    }
+
+    Ok(score)
+}
@@ -4,10 +4,14 @@ use std::{
     process::exit,
 };
 
+use numeric_answer::{NumericAnswer, Tolerance};
 use rand::{Rng, SeedableRng};
 
+use align::{alignment_score, best_alignment_score, SCORING};
 use rand_chacha::ChaChaRng;
 
+mod align;
+
 fn main() {
     let args = std::env::args().collect::<Vec<String>>();
 
@@ -17,32 +21,111 @@ fn main() {
     args[2].hash(&mut s);
     let seed = s.finish();
 
-    let mut rng = ChaChaRng::seed_from_u64(seed);
+    let mode = Mode::from_env();
+    let format = Format::from_env();
 
-    const LENGTH: usize = 100;
+    let sequences = generate_sequences(seed, mode);
+    let ids = sequence_ids(format, sequences.len());
 
-    let seq1 = new_sequence(LENGTH, &mut rng);
-    let seq2 = new_sequence(LENGTH, &mut rng);
+    match (args[1].as_str(), mode) {
+        ("generate", _) => {
+            if let Mode::Alignment = mode {
+                SCORING.print();
+            }
 
-    match args[1].as_str() {
-        "generate" => {
-            println!("{}", seq1);
-            println!("{}", seq2);
+            print_sequences(&sequences, &ids, format);
         }
-        "validate" => {
+        ("validate", Mode::Alignment) => {
+            let (seq1, seq2) = (&sequences[0], &sequences[1]);
+
+            let mut buffer = String::new();
+            std::io::Read::read_to_string(&mut std::io::stdin(), &mut buffer).unwrap();
+
+            let mut lines = buffer.lines().map(str::trim).collect::<Vec<&str>>();
+
+            // Blank lines after the answer are ignored, as the other judges
+            // only read the first line.
+            while lines.last() == Some(&"") {
+                lines.pop();
+            }
+
+            // Either just the score, or the alignment itself.
+            let score = match lines.as_slice() {
+                [score] => score
+                    .parse::<i64>()
+                    .graceful_expect("Invalid input. Expected an integer score."),
+                [row1, row2] => match alignment_score(row1, row2, seq1, seq2, &SCORING) {
+                    Ok(score) => score,
+                    Err(e) => {
+                        eprintln!("{}", e);
+                        exit(1);
+                    }
+                },
+                _ => {
+                    eprintln!("Expected a score or a two line alignment.");
+                    exit(1);
+                }
+            };
+
+            if score == best_alignment_score(seq1, seq2, &SCORING) {
+                exit(0);
+            } else {
+                exit(1);
+            }
+        }
+        ("validate", Mode::Similarity) => {
+            let mut buffer = String::new();
+            std::io::stdin().read_line(&mut buffer).unwrap();
+
+            let similarity = similarity(&sequences[0], &sequences[1]);
+
+            let answer = similarity_answer();
+
+            let input_similarity = answer.parse(buffer.trim()).unwrap_or_else(|e| {
+                eprintln!("{}", e);
+                exit(1);
+            });
+
+            if answer.matches(input_similarity, similarity) {
+                exit(0);
+            } else {
+                exit(1);
+            }
+        }
+        ("validate", Mode::Pairs) => {
             let mut buffer = String::new();
             std::io::stdin().read_line(&mut buffer).unwrap();
 
-            let similarity = similarity(&seq1, &seq2);
+            let parts = buffer
+                .split(|c: char| c == ',' || c.is_whitespace())
+                .filter(|part| !part.is_empty())
+                .collect::<Vec<&str>>();
+
+            let [id1, id2, value] = parts.as_slice() else {
+                eprintln!("Expected two sequence IDs followed by their similarity.");
+                exit(1);
+            };
+
+            let find_id = |id: &str| match ids.iter().position(|other| other == id) {
+                Some(index) => index,
+                None => {
+                    eprintln!("Unknown sequence ID `{}`.", id);
+                    exit(1);
+                }
+            };
+            let mut pair = [find_id(id1), find_id(id2)];
+            pair.sort();
+
+            let answer = similarity_answer();
+
+            let input_similarity = answer.parse(value).unwrap_or_else(|e| {
+                eprintln!("{}", e);
+                exit(1);
+            });
 
-            let input_similarity = buffer
-                .trim()
-                .parse::<f64>()
-                .map_err(|_| ())
-                .and_then(|x| if x >= 0.0 && x <= 1.0 { Ok(x) } else { Err(()) })
-                .graceful_expect("Invalid input. Expected a number beteen 0 and 1.");
+            let (i, j, similarity) = most_similar_pair(&sequences).unwrap();
 
-            if (similarity - input_similarity).abs() < 0.01 {
+            if pair == [i, j] && answer.matches(input_similarity, similarity) {
                 exit(0);
             } else {
                 exit(1);
@@ -52,6 +135,17 @@ fn main() {
     }
 }
 
+/// Similarities are given to 3 decimal places, which can be relaxed with the
+/// `ANSWER_DECIMAL_PLACES` and `ANSWER_TOLERANCE` environment variables.
+fn similarity_answer() -> NumericAnswer {
+    NumericAnswer {
+        decimal_places: Some(3),
+        tolerance: Tolerance::Rounded,
+        range: Some((0.0, 1.0)),
+    }
+    .with_env_overrides()
+}
+
 trait GracefulExpect<T> {
     fn graceful_expect(self, message: &str) -> T;
 }
@@ -68,6 +162,157 @@ impl<T, E> GracefulExpect<T> for Result<T, E> {
     }
 }
 
+/// Selected with the `DNA_MODE` environment variable.
+#[derive(Clone, Copy)]
+enum Mode {
+    /// Two sequences of equal length compared position by position.
+    Similarity,
+    /// Two sequences of different lengths, scored by global alignment.
+    Alignment,
+    /// Several sequences of equal length, looking for the most similar pair.
+    Pairs,
+}
+
+impl Mode {
+    fn from_env() -> Mode {
+        match std::env::var("DNA_MODE").as_deref() {
+            Err(_) | Ok("similarity") => Mode::Similarity,
+            Ok("alignment") => Mode::Alignment,
+            Ok("pairs") => Mode::Pairs,
+            Ok(other) => panic!("Unknown DNA mode: {}", other),
+        }
+    }
+}
+
+/// Selected with the `DNA_FORMAT` environment variable.
+#[derive(Clone, Copy)]
+enum Format {
+    /// One sequence per line.
+    Lines,
+    /// FASTA records with a `>` header line and the sequence wrapped at 60
+    /// nucleotides per line.
+    Fasta,
+}
+
+impl Format {
+    fn from_env() -> Format {
+        match std::env::var("DNA_FORMAT").as_deref() {
+            Err(_) | Ok("lines") => Format::Lines,
+            Ok("fasta") => Format::Fasta,
+            Ok(other) => panic!("Unknown DNA format: {}", other),
+        }
+    }
+}
+
+/// Sequences are identified by their FASTA header, or by their line number
+/// (starting from 1) when there are no headers.
+fn sequence_ids(format: Format, count: usize) -> Vec<String> {
+    (1..=count)
+        .map(|i| match format {
+            Format::Lines => i.to_string(),
+            Format::Fasta => format!("seq{}", i),
+        })
+        .collect()
+}
+
+fn print_sequences(sequences: &[String], ids: &[String], format: Format) {
+    const FASTA_WIDTH: usize = 60;
+
+    for (sequence, id) in sequences.iter().zip(ids) {
+        match format {
+            Format::Lines => println!("{}", sequence),
+            Format::Fasta => {
+                println!(">{}", id);
+
+                // Sequences are ASCII so splitting by bytes is safe.
+                for line in sequence.as_bytes().chunks(FASTA_WIDTH) {
+                    println!("{}", std::str::from_utf8(line).unwrap());
+                }
+            }
+        }
+    }
+}
+
+/// Every sequence descends from a common ancestor so how similar they are
+/// depends on the mutation rates rather than being left to chance. Moves on
+/// to the next seed if deletions leave any sequence empty.
+fn generate_sequences(seed: u64, mode: Mode) -> Vec<String> {
+    let sequences = derive_sequences(seed, mode);
+
+    if sequences.iter().any(|s| s.is_empty()) {
+        return generate_sequences(seed.wrapping_add(1), mode);
+    }
+
+    sequences
+}
+
+fn derive_sequences(seed: u64, mode: Mode) -> Vec<String> {
+    let mut rng = ChaChaRng::seed_from_u64(seed);
+
+    const LENGTH: usize = 100;
+
+    let ancestor = new_sequence(LENGTH, &mut rng);
+
+    match mode {
+        Mode::Similarity | Mode::Alignment => {
+            let rates = MutationRates::new(mode, &mut rng);
+
+            let mut seq1 = descend(&ancestor, &rates, &mut rng);
+            let mut seq2 = descend(&ancestor, &rates, &mut rng);
+
+            if let Mode::Similarity = mode {
+                // Positional comparison needs sequences of equal length.
+                let length = seq1.len().min(seq2.len());
+                seq1.truncate(length);
+                seq2.truncate(length);
+            }
+
+            vec![seq1, seq2]
+        }
+        Mode::Pairs => {
+            let count = rng.gen_range(5..=10);
+
+            // Each sequence gets its own rates so some pairs are much closer
+            // than others.
+            let mut sequences = (0..count)
+                .map(|_| {
+                    let rates = MutationRates::new(mode, &mut rng);
+                    descend(&ancestor, &rates, &mut rng)
+                })
+                .collect::<Vec<String>>();
+
+            let length = sequences.iter().map(|s| s.len()).min().unwrap();
+            sequences.iter_mut().for_each(|s| s.truncate(length));
+
+            if most_similar_pair(&sequences).is_none() {
+                return derive_sequences(seed.wrapping_add(1), mode);
+            }
+
+            sequences
+        }
+    }
+}
+
+/// The indices (smallest first) and similarity of the most similar pair of
+/// sequences. Returns None if more than one pair is the most similar.
+fn most_similar_pair(sequences: &[String]) -> Option<(usize, usize, f64)> {
+    let mut pairs = vec![];
+
+    for i in 0..sequences.len() {
+        for j in i + 1..sequences.len() {
+            pairs.push((i, j, similarity(&sequences[i], &sequences[j])));
+        }
+    }
+
+    pairs.sort_by(|a, b| b.2.total_cmp(&a.2));
+
+    if pairs.len() > 1 && pairs[0].2 == pairs[1].2 {
+        return None;
+    }
+
+    pairs.first().copied()
+}
+
 static NEUCLEOTIDES: [char; 4] = ['A', 'C', 'G', 'T'];
 
 fn new_sequence<R: Rng>(length: usize, rng: &mut R) -> String {
@@ -79,6 +324,82 @@ fn new_sequence<R: Rng>(length: usize, rng: &mut R) -> String {
     sequence
 }
 
+/// Chance per nucleotide of each kind of mutation when deriving a sequence
+/// from its ancestor.
+struct MutationRates {
+    substitution: f64,
+    insertion: f64,
+    deletion: f64,
+    /// A short run of nucleotides being duplicated (a tandem repeat).
+    repeat: f64,
+}
+
+impl MutationRates {
+    /// Rates can be set with `DNA_SUBSTITUTION_RATE`, `DNA_INSERTION_RATE`,
+    /// `DNA_DELETION_RATE` and `DNA_REPEAT_RATE`. Otherwise the substitution
+    /// rate is random for each seed so that answers spread out, and indels
+    /// and repeats are only used for alignment where lengths can differ.
+    /// Deletions are capped at MAX_DELETION_RATE so some of the ancestor
+    /// survives.
+    fn new<R: Rng>(mode: Mode, rng: &mut R) -> MutationRates {
+        const MAX_DELETION_RATE: f64 = 0.9;
+
+        let (substitution, insertion, deletion, repeat) = match mode {
+            Mode::Similarity | Mode::Pairs => (rng.gen_range(0.0..0.75), 0.0, 0.0, 0.0),
+            Mode::Alignment => (rng.gen_range(0.0..0.4), 0.03, 0.03, 0.01),
+        };
+
+        MutationRates {
+            substitution: rate_from_env("DNA_SUBSTITUTION_RATE").unwrap_or(substitution),
+            insertion: rate_from_env("DNA_INSERTION_RATE").unwrap_or(insertion),
+            deletion: rate_from_env("DNA_DELETION_RATE")
+                .unwrap_or(deletion)
+                .min(MAX_DELETION_RATE),
+            repeat: rate_from_env("DNA_REPEAT_RATE").unwrap_or(repeat),
+        }
+    }
+}
+
+fn rate_from_env(name: &str) -> Option<f64> {
+    let rate = std::env::var(name).ok()?;
+
+    match rate.parse::<f64>() {
+        Ok(rate) if (0.0..=1.0).contains(&rate) => Some(rate),
+        _ => panic!("{} should be a number between 0 and 1.", name),
+    }
+}
+
+/// Copies the ancestor with point mutations, insertions, deletions and
+/// tandem repeats applied at the given rates.
+fn descend<R: Rng>(ancestor: &str, rates: &MutationRates, rng: &mut R) -> String {
+    let mut descendant = String::new();
+
+    for n in ancestor.chars() {
+        if rng.gen_bool(rates.deletion) {
+            continue;
+        }
+
+        if rng.gen_bool(rates.substitution) {
+            let others = NEUCLEOTIDES.iter().filter(|&&m| m != n).collect::<Vec<_>>();
+            descendant.push(*others[rng.gen_range(0..others.len())]);
+        } else {
+            descendant.push(n);
+        }
+
+        if rng.gen_bool(rates.insertion) {
+            descendant.push(NEUCLEOTIDES[rng.gen_range(0..NEUCLEOTIDES.len())]);
+        }
+
+        if rng.gen_bool(rates.repeat) {
+            let length = rng.gen_range(2..=6).min(descendant.len());
+            let run = descendant[descendant.len() - length..].to_string();
+            descendant.push_str(&run);
+        }
+    }
+
+    descendant
+}
+
 fn similarity(seq1: &str, seq2: &str) -> f64 {
     let mut matches = 0;
     for (n1, n2) in seq1.chars().zip(seq2.chars()) {
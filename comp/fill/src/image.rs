@@ -0,0 +1,52 @@
+use std::{fs::File, io::BufWriter};
+
+use crate::{Canvas, Colour, Mode, PALETTE_SIZE};
+
+/// RGB values for each colour, starting with white for empty pixels and
+/// black for the single colour image.
+const PALETTE: [[u8; 3]; PALETTE_SIZE as usize] = [
+    [255, 255, 255],
+    [0, 0, 0],
+    [220, 50, 47],
+    [38, 139, 210],
+    [133, 153, 0],
+    [181, 137, 0],
+];
+
+/// Prints the canvas in the plain (ASCII) netpbm formats. Single colour
+/// images are a PBM (`P1`) where `1` is a filled pixel, multi-colour images
+/// are a PGM (`P2`) where each value is the colour's index.
+pub fn print_netpbm(canvas: &Canvas, mode: Mode) {
+    let (width, height) = (canvas[0].len(), canvas.len());
+
+    match mode {
+        Mode::Single => println!("P1\n{} {}", width, height),
+        Mode::Colour => println!("P2\n{} {}\n{}", width, height, PALETTE_SIZE - 1),
+    }
+
+    for row in canvas {
+        println!(
+            "{}",
+            row.iter()
+                .map(|colour| colour.to_string())
+                .collect::<Vec<String>>()
+                .join(" ")
+        );
+    }
+}
+
+/// Writes the canvas to a PNG so it can be shown on the problem page.
+pub fn write_png(canvas: &Canvas, path: &str) -> Result<(), png::EncodingError> {
+    let (width, height) = (canvas[0].len(), canvas.len());
+
+    let file = File::create(path)?;
+    let mut encoder = png::Encoder::new(BufWriter::new(file), width as u32, height as u32);
+    encoder.set_color(png::ColorType::Indexed);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder.set_palette(PALETTE.concat());
+
+    let data = canvas.iter().flatten().copied().collect::<Vec<Colour>>();
+
+    let mut writer = encoder.write_header()?;
+    writer.write_image_data(&data)
+}
@@ -9,7 +9,17 @@ use rand::{Rng, SeedableRng};
 
 use rand_chacha::ChaChaRng;
 
-type Canvas = Vec<Vec<bool>>;
+mod image;
+
+/// Index into the palette. 0 is the empty background and the single colour
+/// images only use 1.
+type Colour = u8;
+type Canvas = Vec<Vec<Colour>>;
+
+const EMPTY: Colour = 0;
+
+/// Colours 0 to PALETTE_SIZE - 1 are used by multi-colour images.
+const PALETTE_SIZE: Colour = 6;
 
 fn main() {
     let args = std::env::args().collect::<Vec<String>>();
@@ -20,17 +30,34 @@ fn main() {
     args[2].hash(&mut s);
     let seed = s.finish();
 
-    let mut canvas = create_canvas(100, 100);
-    paint_crude_circle(&mut canvas, point(50, 50), 35, &Brush { radius: 1 }, seed);
+    let mut problem = generate_problem(seed, &Config::from_env());
 
     match args[1].as_str() {
         "generate" => {
-            print_canvas(&canvas);
+            match problem.mode {
+                Mode::Single => println!("{}, {}", problem.start.x, problem.start.y),
+                Mode::Colour => println!(
+                    "{}, {}, {}",
+                    problem.start.x, problem.start.y, problem.fill_colour
+                ),
+            }
+            match std::env::var("FILL_FORMAT").as_deref() {
+                Err(_) | Ok("text") => print_canvas(&problem.canvas),
+                Ok("netpbm") => image::print_netpbm(&problem.canvas, problem.mode),
+                Ok(other) => panic!("Unknown fill format: {}", other),
+            }
+
+            if let Ok(path) = std::env::var("FILL_PNG") {
+                image::write_png(&problem.canvas, &path).expect("Failed to write fill image.");
+            }
         }
         "validate" => {
-            let mut count = 0;
-            assert!(!canvas[50][50]);
-            flood(&mut canvas, 50, 50, &mut count);
+            let count = flood(
+                &mut problem.canvas,
+                problem.start,
+                problem.fill_colour,
+                problem.connectivity,
+            );
 
             let mut buffer = String::new();
             std::io::stdin().read_line(&mut buffer).unwrap();
@@ -66,12 +93,203 @@ impl<T, E> GracefulExpect<T> for Result<T, E> {
     }
 }
 
+struct Problem {
+    mode: Mode,
+    canvas: Canvas,
+    start: Point,
+    fill_colour: Colour,
+    connectivity: Connectivity,
+}
+
+/// Selected with the `FILL_MODE` environment variable.
+#[derive(Clone, Copy, Debug)]
+enum Mode {
+    /// A single crude circle on an empty canvas, filled from an empty pixel.
+    Single,
+    /// Several overlapping shapes in different colours, bucket filled with a
+    /// given colour from any pixel.
+    Colour,
+}
+
+impl Mode {
+    fn from_env() -> Mode {
+        match std::env::var("FILL_MODE").as_deref() {
+            Err(_) | Ok("single") => Mode::Single,
+            Ok("colour") => Mode::Colour,
+            Ok(other) => panic!("Unknown fill mode: {}", other),
+        }
+    }
+}
+
+/// Which neighbouring pixels the fill spreads to.
+#[derive(Clone, Copy)]
+enum Connectivity {
+    /// Only pixels that share an edge.
+    Four,
+    /// Pixels that share an edge or a corner.
+    Eight,
+}
+
+impl Connectivity {
+    fn from_env() -> Connectivity {
+        match std::env::var("FILL_CONNECTIVITY").as_deref() {
+            Err(_) | Ok("4") => Connectivity::Four,
+            Ok("8") => Connectivity::Eight,
+            Ok(other) => panic!("Unknown connectivity: {}", other),
+        }
+    }
+
+    fn offsets(self) -> &'static [Point] {
+        const FOUR: [Point; 4] = [
+            Point { x: 1, y: 0 },
+            Point { x: -1, y: 0 },
+            Point { x: 0, y: 1 },
+            Point { x: 0, y: -1 },
+        ];
+        const EIGHT: [Point; 8] = [
+            Point { x: 1, y: 0 },
+            Point { x: -1, y: 0 },
+            Point { x: 0, y: 1 },
+            Point { x: 0, y: -1 },
+            Point { x: 1, y: 1 },
+            Point { x: 1, y: -1 },
+            Point { x: -1, y: 1 },
+            Point { x: -1, y: -1 },
+        ];
+
+        match self {
+            Connectivity::Four => &FOUR,
+            Connectivity::Eight => &EIGHT,
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Config {
+    /// Side length of the square canvas.
+    size: i32,
+    mode: Mode,
+    connectivity: Connectivity,
+}
+
+impl Config {
+    fn from_env() -> Config {
+        let size = match std::env::var("FILL_SIZE") {
+            Ok(size) => size
+                .parse::<i32>()
+                .expect("FILL_SIZE should be an integer."),
+            Err(_) => 100,
+        };
+
+        Config {
+            size,
+            mode: Mode::from_env(),
+            connectivity: Connectivity::from_env(),
+        }
+    }
+}
+
+/// The shapes scale with the size of the canvas.
+fn generate_problem(seed: u64, config: &Config) -> Problem {
+    let Config {
+        size,
+        mode,
+        connectivity,
+    } = *config;
+
+    assert!(size >= 10, "The canvas must be at least 10 pixels wide.");
+
+    let mut rng = ChaChaRng::seed_from_u64(seed);
+
+    let brush_radius = (size / 100).max(1);
+
+    let mut canvas = create_canvas(size, size);
+
+    let (start, fill_colour) = match mode {
+        Mode::Single => {
+            let brush = Brush {
+                radius: brush_radius,
+                colour: 1,
+            };
+            paint_crude_circle(
+                &mut canvas,
+                point(size / 2, size / 2),
+                size * 35 / 100,
+                &brush,
+                &mut rng,
+            );
+
+            // Any empty pixel is a valid place to start filling from.
+            let start = loop {
+                let p = point(rng.gen_range(0..size), rng.gen_range(0..size));
+                if canvas[p.y as usize][p.x as usize] == EMPTY {
+                    break p;
+                }
+            };
+
+            (start, 1)
+        }
+        Mode::Colour => {
+            paint_shapes(&mut canvas, brush_radius, &mut rng);
+
+            let start = point(rng.gen_range(0..size), rng.gen_range(0..size));
+
+            // Filling with the colour that is already there wouldn't change
+            // anything, so always pick a different one.
+            let existing = canvas[start.y as usize][start.x as usize];
+            let fill_colour = loop {
+                let colour = rng.gen_range(0..PALETTE_SIZE);
+                if colour != existing {
+                    break colour;
+                }
+            };
+
+            (start, fill_colour)
+        }
+    };
+
+    Problem {
+        mode,
+        canvas,
+        start,
+        fill_colour,
+        connectivity,
+    }
+}
+
+/// Paints several overlapping circles, lines and rectangles in random colours.
+fn paint_shapes<R: Rng>(canvas: &mut Canvas, brush_radius: i32, rng: &mut R) {
+    let size = canvas.len() as i32;
+
+    let shapes = rng.gen_range(4..=8);
+
+    for _ in 0..shapes {
+        let brush = Brush {
+            radius: brush_radius,
+            colour: rng.gen_range(1..PALETTE_SIZE),
+        };
+
+        let a = point(rng.gen_range(0..size), rng.gen_range(0..size));
+        let b = point(rng.gen_range(0..size), rng.gen_range(0..size));
+
+        match rng.gen_range(0..3) {
+            0 => {
+                let radius = rng.gen_range(size / 10..=size * 35 / 100);
+                paint_crude_circle(canvas, a, radius, &brush, rng);
+            }
+            1 => paint_line(canvas, a, b, &brush),
+            _ => paint_rectangle(canvas, a, b, &brush),
+        }
+    }
+}
+
 fn create_canvas(width: i32, height: i32) -> Canvas {
-    vec![vec![false; width as usize]; height as usize]
+    vec![vec![EMPTY; width as usize]; height as usize]
 }
 
 struct Brush {
     radius: i32,
+    colour: Colour,
 }
 
 fn inside_canvas(canvas: &Canvas, x: i32, y: i32) -> bool {
@@ -117,76 +335,154 @@ impl Mul<i32> for Point {
     }
 }
 
-fn lerp(a: f64, b: f64, t: f64) -> f64 {
-    a + (b - a) * t
+/// Moves a towards b by numerator / denominator of the way, rounding towards a.
+fn lerp(a: Point, b: Point, numerator: i32, denominator: i32) -> Point {
+    let d = (b - a) * numerator;
+    a + point(d.x / denominator, d.y / denominator)
 }
 
 fn point(x: i32, y: i32) -> Point {
     Point { x, y }
 }
 
-fn dist(p1: Point, p2: Point) -> f64 {
-    let x = p1.x as f64 - p2.x as f64;
-    let y = p1.y as f64 - p2.y as f64;
-    (x * x + y * y).sqrt()
-}
-
 fn draw_brush(canvas: &mut Canvas, p: Point, brush: &Brush) {
     for x in p.x - brush.radius..=p.x + brush.radius {
         for y in p.y - brush.radius..=p.y + brush.radius {
-            if inside_canvas(canvas, x, y) && dist(p, point(x, y)) <= brush.radius as f64 {
-                canvas[y as usize][x as usize] = true;
+            let d = point(x, y) - p;
+            if inside_canvas(canvas, x, y) && d.x * d.x + d.y * d.y <= brush.radius * brush.radius {
+                canvas[y as usize][x as usize] = brush.colour;
             }
         }
     }
 }
 
+/// Bresenham's line algorithm, drawing the brush at every point from start to
+/// end inclusive.
 fn paint_line(canvas: &mut Canvas, start: Point, end: Point, brush: &Brush) {
-    let steps = (dist(start, end) + 1.) as i32;
+    let dx = (end.x - start.x).abs();
+    let dy = -(end.y - start.y).abs();
+    let step = point((end.x - start.x).signum(), (end.y - start.y).signum());
 
-    for i in 0..steps {
-        let dif = end - start;
+    let mut error = dx + dy;
+    let mut p = start;
 
-        let point_in_line = i as f64 / steps as f64;
+    loop {
+        draw_brush(canvas, p, brush);
 
-        let x_ = dif.x as f64 * point_in_line;
-        let y_ = dif.y as f64 * point_in_line;
+        if p == end {
+            break;
+        }
 
-        let p = start + point(x_ as i32, y_ as i32);
-        draw_brush(canvas, p, brush);
+        let e2 = error * 2;
+
+        if e2 >= dy {
+            error += dy;
+            p.x += step.x;
+        }
+
+        if e2 <= dx {
+            error += dx;
+            p.y += step.y;
+        }
     }
 }
 
-fn paint_crude_circle(canvas: &mut Canvas, center: Point, radius: i32, brush: &Brush, seed: u64) {
-    let start = center + point(radius, 0);
+/// Paints the outline of the rectangle with corners at a and b.
+fn paint_rectangle(canvas: &mut Canvas, a: Point, b: Point, brush: &Brush) {
+    let corners = [a, point(b.x, a.y), b, point(a.x, b.y)];
+
+    for i in 0..corners.len() {
+        paint_line(canvas, corners[i], corners[(i + 1) % corners.len()], brush);
+    }
+}
+
+/// Points on a circle around the origin in order of increasing angle,
+/// starting at (radius, 0), found with the midpoint circle algorithm.
+fn circle_points(radius: i32) -> Vec<Point> {
+    if radius <= 0 {
+        return vec![point(0, 0)];
+    }
+
+    // The first octant, from (radius, 0) up to the diagonal.
+    let mut octant = vec![];
+    let (mut x, mut y) = (radius, 0);
+    let mut decision = 1 - radius;
+
+    while x >= y {
+        octant.push(point(x, y));
+        y += 1;
+
+        if decision < 0 {
+            decision += 2 * y + 1;
+        } else {
+            x -= 1;
+            decision += 2 * (y - x) + 1;
+        }
+    }
 
-    let (mut brush_x, mut brush_y) = (start.x as f64, start.y as f64);
+    // The second octant mirrors the first across the diagonal.
+    let mut quadrant = octant.clone();
+    quadrant.extend(octant.iter().rev().map(|p| point(p.y, p.x)));
+    quadrant.dedup();
+
+    // Leave off (0, radius) as it starts the next quadrant.
+    quadrant.pop();
+
+    let mut points = vec![];
+    for _ in 0..4 {
+        points.extend(quadrant.iter().copied());
+        quadrant.iter_mut().for_each(|p| *p = point(-p.y, p.x));
+    }
+
+    points
+}
+
+/// Paints a wobbly circle by steering the brush towards points at a random
+/// distance from the centre. Only uses integer arithmetic so the image is the
+/// same on every platform.
+fn paint_crude_circle<R: Rng>(
+    canvas: &mut Canvas,
+    center: Point,
+    radius: i32,
+    brush: &Brush,
+    rng: &mut R,
+) {
+    let start = center + point(radius, 0);
 
     const POINTS: i32 = 40;
-    const STABISATION: f64 = 0.6;
-    const WIGGLE: i32 = 15;
 
-    let mut rng = ChaChaRng::seed_from_u64(seed);
+    // The brush moves 2/5 of the way to each target.
+    const STABISATION: (i32, i32) = (2, 5);
+
+    // The brush position is kept in 1/256ths of a pixel.
+    const SUBPIXELS: i32 = 256;
+
+    // 15 pixels for the original radius of 35.
+    let wiggle = (radius * 3 / 7).max(1);
 
-    for theta in 0..POINTS {
-        let theta = (theta as f64 / POINTS as f64) * 2. * std::f64::consts::PI;
-        let from = point(brush_x as i32, brush_y as i32);
+    let mut brush_position = start * SUBPIXELS;
 
-        let radius = (radius + rng.gen::<i32>() % WIGGLE - (WIGGLE / 2)) as f64;
+    let pixel = |p: Point| point(p.x.div_euclid(SUBPIXELS), p.y.div_euclid(SUBPIXELS));
 
-        let x = center.x + (radius * theta.cos()) as i32;
-        let y = center.y + (radius * theta.sin()) as i32;
+    for i in 0..POINTS {
+        let from = pixel(brush_position);
 
-        let target = point(x, y);
+        let radius = radius + rng.gen::<i32>() % wiggle - (wiggle / 2);
 
-        brush_x = lerp(brush_x, target.x as f64, 1.0 - STABISATION);
-        brush_y = lerp(brush_y, target.y as f64, 1.0 - STABISATION);
+        let perimeter = circle_points(radius);
+        let target = center + perimeter[i as usize * perimeter.len() / POINTS as usize];
 
-        let to = point(brush_x as i32, brush_y as i32);
-        paint_line(canvas, from, to, brush);
+        brush_position = lerp(
+            brush_position,
+            target * SUBPIXELS,
+            STABISATION.0,
+            STABISATION.1,
+        );
+
+        paint_line(canvas, from, pixel(brush_position), brush);
     }
 
-    paint_line(canvas, point(brush_x as i32, brush_y as i32), start, brush);
+    paint_line(canvas, pixel(brush_position), start, brush);
 }
 
 fn print_canvas(canvas: &Canvas) {
@@ -194,24 +490,181 @@ fn print_canvas(canvas: &Canvas) {
         println!(
             "{}",
             row.iter()
-                .map(|&b| if b { "1" } else { "0" })
-                .collect::<Vec<&str>>()
+                .map(|colour| colour.to_string())
+                .collect::<Vec<String>>()
                 .join(", ")
         );
     }
 }
 
-fn flood(canvas: &mut Canvas, x: i32, y: i32, count: &mut u32) {
-    if !inside_canvas(canvas, x, y) || canvas[y as usize][x as usize] {
-        return;
+/// Bucket fills the region of the same colour as start with fill_colour and
+/// returns how many pixels changed. Uses an explicit stack so large canvases
+/// can't overflow the call stack, and paints pixels as they are pushed so each
+/// is only pushed once.
+fn flood(
+    canvas: &mut Canvas,
+    start: Point,
+    fill_colour: Colour,
+    connectivity: Connectivity,
+) -> u32 {
+    if !inside_canvas(canvas, start.x, start.y) {
+        return 0;
+    }
+
+    let target = canvas[start.y as usize][start.x as usize];
+
+    if target == fill_colour {
+        return 0;
+    }
+
+    canvas[start.y as usize][start.x as usize] = fill_colour;
+
+    let mut count = 0;
+    let mut stack = vec![start];
+
+    while let Some(p) = stack.pop() {
+        count += 1;
+
+        for &offset in connectivity.offsets() {
+            let next = p + offset;
+
+            if !inside_canvas(canvas, next.x, next.y)
+                || canvas[next.y as usize][next.x as usize] != target
+            {
+                continue;
+            }
+
+            canvas[next.y as usize][next.x as usize] = fill_colour;
+            stack.push(next);
+        }
+    }
+
+    count
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// FNV-1a over the canvas, which unlike `DefaultHasher` is stable across
+    /// Rust versions and platforms.
+    fn checksum(canvas: &Canvas) -> u64 {
+        let mut hash: u64 = 0xcbf29ce484222325;
+
+        for &colour in canvas.iter().flatten() {
+            hash ^= colour as u64;
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+
+        hash
+    }
+
+    fn solve(seed: u64, size: i32, mode: Mode) -> (Problem, u32) {
+        let config = Config {
+            size,
+            mode,
+            connectivity: Connectivity::Four,
+        };
+
+        let problem = generate_problem(seed, &config);
+        let answer = flood(
+            &mut problem.canvas.clone(),
+            problem.start,
+            problem.fill_colour,
+            problem.connectivity,
+        );
+
+        (problem, answer)
     }
 
-    canvas[y as usize][x as usize] = true;
+    /// Problems generated by the integer rasteriser, pinned so that any change
+    /// to the generated images (and the answers with them) is caught.
+    #[test]
+    fn reference_problems_are_unchanged() {
+        // (seed, size, mode, canvas checksum, answer)
+        const REFERENCES: [(u64, i32, Mode, u64, u32); 4] = [
+            (0, 100, Mode::Single, 0xbff65d491a5eba78, 2379),
+            (1, 100, Mode::Single, 0x4af133ca6e83509d, 7291),
+            (2, 100, Mode::Colour, 0x230593cc9ab9da90, 5229),
+            (3, 500, Mode::Colour, 0x8e5796ac329ecef3, 3700),
+        ];
+
+        for (seed, size, mode, expected_checksum, expected_answer) in REFERENCES {
+            let (problem, answer) = solve(seed, size, mode);
+
+            assert_eq!(checksum(&problem.canvas), expected_checksum, "seed {}", seed);
+            assert_eq!(answer, expected_answer, "seed {}", seed);
+        }
+    }
 
-    *count += 1;
+    /// The integer rasteriser can't draw exactly the same pixels as the
+    /// floating point one it replaced, so answers for existing seeds changed.
+    /// Compares against problems saved from the old generator to make sure
+    /// they only changed a little: the start and fill colour are the same and
+    /// the shapes are in the same places.
+    #[test]
+    fn close_to_floating_point_rasteriser() {
+        const MAX_DIFFERENT_PIXELS: f64 = 0.05;
+        const MAX_ANSWER_CHANGE: f64 = 0.1;
+
+        // (seed, mode, problem from the old generator, its answer)
+        let references = [
+            (0, Mode::Single, include_str!("../references/single-0.txt"), 2249),
+            (1, Mode::Single, include_str!("../references/single-1.txt"), 7395),
+            (2, Mode::Colour, include_str!("../references/colour-2.txt"), 5264),
+            (3, Mode::Colour, include_str!("../references/colour-3.txt"), 4787),
+        ];
+
+        for (seed, mode, reference, old_answer) in references {
+            let mut lines = reference.lines();
+
+            let start = lines
+                .next()
+                .unwrap()
+                .split(", ")
+                .map(|n| n.parse::<i32>().unwrap())
+                .collect::<Vec<i32>>();
+
+            let old_canvas = lines
+                .map(|row| {
+                    row.split(", ")
+                        .map(|colour| colour.parse::<Colour>().unwrap())
+                        .collect()
+                })
+                .collect::<Canvas>();
+
+            let (problem, answer) = solve(seed, old_canvas.len() as i32, mode);
+
+            assert_eq!(point(start[0], start[1]), problem.start, "seed {}", seed);
+            if let Mode::Colour = mode {
+                assert_eq!(start[2] as Colour, problem.fill_colour, "seed {}", seed);
+            }
 
-    flood(canvas, x + 1, y, count);
-    flood(canvas, x - 1, y, count);
-    flood(canvas, x, y + 1, count);
-    flood(canvas, x, y - 1, count);
+            let pixels = old_canvas.len() * old_canvas[0].len();
+            let different = old_canvas
+                .iter()
+                .flatten()
+                .zip(problem.canvas.iter().flatten())
+                .filter(|(a, b)| a != b)
+                .count();
+
+            assert!(
+                different as f64 <= pixels as f64 * MAX_DIFFERENT_PIXELS,
+                "seed {}: {} of {} pixels changed",
+                seed,
+                different,
+                pixels
+            );
+
+            let change = (answer as f64 - old_answer as f64).abs() / old_answer as f64;
+
+            assert!(
+                change <= MAX_ANSWER_CHANGE,
+                "seed {}: answer changed from {} to {}",
+                seed,
+                old_answer,
+                answer
+            );
+        }
+    }
 }
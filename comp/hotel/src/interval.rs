@@ -0,0 +1,91 @@
+//! Periods of time, such as a guest's stay or a cleaning job.
+//!
+//! Periods are half-open: a guest staying from `start` to `end` has left the
+//! room at `end`, so another period can begin at that same second.
+
+pub type Second = i64;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Period {
+    pub start: Second,
+    pub end: Second,
+}
+
+impl Period {
+    pub fn new(start: Second, end: Second) -> Period {
+        assert!(start <= end, "A period can't end before it starts.");
+
+        Period { start, end }
+    }
+
+    pub fn len(&self) -> Second {
+        self.end - self.start
+    }
+
+    /// Whether some second is in both periods. Empty periods overlap
+    /// nothing.
+    pub fn overlaps(&self, other: &Period) -> bool {
+        self.start.max(other.start) < self.end.min(other.end)
+    }
+
+    /// Whether `other` fits entirely within this period.
+    pub fn contains(&self, other: &Period) -> bool {
+        self.start <= other.start && other.end <= self.end
+    }
+
+    /// The time between the end of the earlier period and the start of the
+    /// later one, or zero if they overlap.
+    pub fn gap(&self, other: &Period) -> Second {
+        (other.start - self.end).max(self.start - other.end).max(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaChaRng;
+
+    use super::*;
+
+    /// Compares `overlaps`, `contains` and `gap` against a second by second
+    /// timeline for random pairs of short periods.
+    #[test]
+    fn matches_timeline() {
+        const TIMELINE: Second = 40;
+
+        let mut rng = ChaChaRng::seed_from_u64(0);
+
+        let mut random_period = || {
+            let start = rng.gen_range(0..TIMELINE);
+            Period::new(start, rng.gen_range(start..=TIMELINE))
+        };
+
+        for _ in 0..10_000 {
+            let (a, b) = (random_period(), random_period());
+
+            let seconds = |p: Period| (0..TIMELINE).filter(move |s| (p.start..p.end).contains(s));
+
+            let overlaps = seconds(a).any(|s| seconds(b).any(|t| s == t));
+            let contains = seconds(b).all(|s| seconds(a).any(|t| s == t));
+
+            // The seconds from the end of the earlier period up to the start
+            // of the later one.
+            let (first, second) = if a.start <= b.start { (a, b) } else { (b, a) };
+            let gap = if overlaps || first.end > second.start {
+                0
+            } else {
+                (first.end..second.start).count() as Second
+            };
+
+            assert_eq!(a.overlaps(&b), overlaps, "{:?} and {:?}", a, b);
+
+            // An empty period has no seconds to compare.
+            if b.len() > 0 {
+                assert_eq!(a.contains(&b), contains, "{:?} and {:?}", a, b);
+            }
+
+            assert_eq!(a.gap(&b), gap, "{:?} and {:?}", a, b);
+            assert_eq!(b.gap(&a), gap, "{:?} and {:?}", b, a);
+        }
+    }
+}
@@ -1,6 +1,7 @@
 use std::{
     collections::{hash_map::DefaultHasher, HashMap, HashSet},
     hash::{Hash, Hasher},
+    io::Read,
     process::exit,
 };
 
@@ -8,6 +9,12 @@ use rand::{Rng, SeedableRng};
 
 use rand_chacha::ChaChaRng;
 
+use interval::{Period, Second};
+use schedule::{Cleaning, STAFF_COUNT};
+
+mod interval;
+mod schedule;
+
 fn main() {
     let args = std::env::args().collect::<Vec<String>>();
 
@@ -25,9 +32,13 @@ fn main() {
         }
         "validate" => {
             let mut buffer = String::new();
-            std::io::stdin().read_line(&mut buffer).unwrap();
+            std::io::stdin().read_to_string(&mut buffer).unwrap();
+
+            let mut lines = buffer.lines();
 
-            let colouring = parse_colouring(buffer).graceful_unwrap();
+            let colouring =
+                parse_colouring(lines.next().unwrap_or_default().to_string()).graceful_unwrap();
+            let schedule = parse_schedule(lines).graceful_unwrap();
 
             if colouring.len() < problem.len() {
                 eprintln!("Too few allocations.");
@@ -55,7 +66,11 @@ fn main() {
                 exit(1);
             }
 
-            if !can_clean(&graph).is_yes() {
+            // Teams can show how the rooms get cleaned, otherwise the judge
+            // looks for a way itself.
+            if !schedule.is_empty() {
+                check_schedule(&graph, &schedule).graceful_unwrap();
+            } else if !can_clean(&graph).is_yes() {
                 eprintln!("Rooms could not be cleaned in time.");
                 exit(1);
             }
@@ -98,42 +113,15 @@ struct Occupancy {
 
 static CLEANING_TIME: Second = 1800;
 
-type Second = i64;
-
-#[derive(Clone, Debug)]
-struct Period {
-    start: Second,
-    end: Second,
-}
-
-impl Period {
-    fn conincides(&self, other: &Period) -> bool {
-        (self.start <= other.end && self.start >= other.start)
-            || (self.end <= other.end && self.end >= other.start)
-    }
-
-    fn distance(&self, other: &Period) -> Second {
-        if self.conincides(other) {
-            return 0;
-        }
-
-        if other.start > self.end {
-            return other.start - self.end;
-        } else {
-            return other.end - self.start;
-        }
-    }
-}
-
 /// Create graph of occupancies that cannot be allocated the same room
-/// (meaning that they are within CLEANING_TIME of each other and there
-/// would not be enough time to clean the room between them)
+/// (meaning that they are less than CLEANING_TIME apart and there would not
+/// be enough time to clean the room between them)
 fn create_graph(stays: &Vec<Period>) -> Vec<Occupancy> {
     let mut graph: Graph = stays
         .into_iter()
         .map(|d| Occupancy {
             room: None,
-            duration: d.clone(),
+            duration: *d,
             neighbours: HashSet::new(),
             ignore: false,
         })
@@ -145,7 +133,7 @@ fn create_graph(stays: &Vec<Period>) -> Vec<Occupancy> {
                 continue;
             }
 
-            if graph[i].duration.distance(&graph[j].duration).abs() <= CLEANING_TIME {
+            if graph[i].duration.gap(&graph[j].duration) < CLEANING_TIME {
                 graph[i].neighbours.insert(j);
                 graph[j].neighbours.insert(i);
             }
@@ -202,8 +190,6 @@ struct Job {
     avalible_time_to_complete: Period,
     occupant: OccupancyId,
     prior_occupant: OccupancyId,
-
-    #[allow(unused)] // This is useful for debugging
     room: RoomNumber,
 }
 
@@ -225,181 +211,55 @@ impl CanClean {
     }
 }
 
-fn can_clean(graph: &Graph) -> CanClean {
-    let mut jobs: Vec<Job> = vec![];
-    let mut last_in_room: [Second; 300] = [0; 300];
-    let mut last_occupant_in_room: [i32; 300] = [-1; 300];
+/// The cleaning each room needs between one guest leaving and the next
+/// arriving.
+fn cleaning_jobs(graph: &Graph) -> Vec<Job> {
+    let mut arrivals = (0..graph.len())
+        .filter(|&id| !graph[id].ignore)
+        .collect::<Vec<OccupancyId>>();
 
-    let mut numbered_graph: Vec<(usize, Occupancy)> =
-        graph.clone().into_iter().enumerate().collect();
+    arrivals.sort_by_key(|&id| graph[id].duration.start);
 
-    numbered_graph.sort_by_key(|(_, o)| o.duration.start);
+    let mut last_occupant: HashMap<RoomNumber, OccupancyId> = HashMap::new();
+    let mut jobs = vec![];
 
-    for (id, occ) in numbered_graph {
-        if occ.ignore {
-            continue;
-        }
-
-        assert!(occ.room.is_some());
-
-        let room = occ.room.unwrap();
+    for id in arrivals {
+        assert!(graph[id].room.is_some());
 
-        if last_in_room[room] != 0 {
-            let period = Period {
-                start: last_in_room[room],
-                end: occ.duration.start,
-            };
-
-            assert!(period.end - period.start > CLEANING_TIME);
+        let room = graph[id].room.unwrap();
 
-            let prior_occupant = last_occupant_in_room[room];
+        if let Some(prior_occupant) = last_occupant.insert(room, id) {
+            let period = Period::new(graph[prior_occupant].duration.end, graph[id].duration.start);
 
-            assert_ne!(prior_occupant, -1);
+            assert!(period.len() >= CLEANING_TIME);
 
             jobs.push(Job {
                 avalible_time_to_complete: period,
                 occupant: id,
-                prior_occupant: prior_occupant as usize,
+                prior_occupant,
                 room,
             });
         }
-
-        last_in_room[room] = occ.duration.end;
-        last_occupant_in_room[room] = id as i32;
-    }
-
-    const STAFF_COUNT: usize = 5;
-
-    let mut staff: Vec<Vec<Job>> = vec![Vec::new(); STAFF_COUNT];
-
-    // TODO: try all possible orderings of job allocations
-    for job in jobs {
-        if !try_allocate(&mut staff, &job) {
-            return CanClean::No {
-                troublesome_occupancy: job.occupant,
-                prior_occupancy: job.prior_occupant,
-            };
-        }
     }
 
-    CanClean::Yes
+    jobs
 }
 
-fn try_allocate(staff: &mut Vec<Vec<Job>>, job: &Job) -> bool {
-    // TODO: try all possible orderings of staff allocations
-    for member in staff {
-        let mut new_job_list = member.clone();
-        new_job_list.push(job.clone());
-        if can_fit_job(new_job_list) {
-            member.push(job.clone());
-            return true;
-        }
-    }
-
-    false
-}
+fn can_clean(graph: &Graph) -> CanClean {
+    let jobs = cleaning_jobs(graph);
 
-/// Attempts to find timeslots for each job to be completed
-fn can_fit_job(jobs: Vec<Job>) -> bool {
-    let allocations = jobs
+    let windows = jobs
         .iter()
-        .map(|j| j.avalible_time_to_complete.start)
-        .collect::<Vec<Second>>();
-
-    __can_fit_job(allocations, &jobs, 0, 0)
-}
-
-fn __can_fit_job(allocations: Vec<Second>, jobs: &Vec<Job>, depth: usize, start: usize) -> bool {
-    if depth > allocations.len() {
-        return false;
-    }
-
-    if valid_job_allocations(&allocations) {
-        return true;
-    }
-
-    'a: for i in 0..allocations.len() {
-        if !has_conflicts(i, &allocations) {
-            continue;
-        }
-
-        let mut allocations = allocations.clone();
-
-        // Move i to next available time.
-        match next_avalible_time(i, allocations.clone(), &jobs[i].avalible_time_to_complete) {
-            None => continue 'a,
-            Some(time) => {
-                assert!(
-                    time >= jobs[i].avalible_time_to_complete.start
-                        && time + CLEANING_TIME < jobs[i].avalible_time_to_complete.end
-                );
-
-                allocations[i] = time;
-            }
-        }
-
-        if __can_fit_job(allocations, jobs, depth + 1, start + 1) {
-            return true;
-        }
-    }
-
-    false
-}
-
-fn has_conflicts(index: usize, allocations: &Vec<Second>) -> bool {
-    for i in 0..allocations.len() {
-        if i == index {
-            continue;
-        }
-
-        if (allocations[i] - allocations[index]).abs() < CLEANING_TIME {
-            return true;
-        }
-    }
-
-    return false;
-}
-
-fn next_avalible_time(
-    ignore: usize,
-    mut allocations: Vec<Second>,
-    period: &Period,
-) -> Option<Second> {
-    allocations.remove(ignore);
-
-    for allocation in &allocations {
-        let guess = allocation + CLEANING_TIME;
-
-        if guess < period.start || guess + CLEANING_TIME >= period.end {
-            continue;
-        }
-
-        let mut new_allocations = allocations.clone();
-        new_allocations.push(guess);
-        if valid_job_allocations(&allocations) {
-            return Some(guess);
-        }
+        .map(|j| j.avalible_time_to_complete)
+        .collect::<Vec<Period>>();
+
+    match schedule::schedule(&windows) {
+        Ok(_) => CanClean::Yes,
+        Err(j) => CanClean::No {
+            troublesome_occupancy: jobs[j].occupant,
+            prior_occupancy: jobs[j].prior_occupant,
+        },
     }
-
-    None
-}
-
-/// Asserts that one cleaning staff member does not have to clean two rooms at the same time.
-/// Checks that all of the cleaning periods start at least CLEANING_TIME apart.
-fn valid_job_allocations(allocations: &Vec<Second>) -> bool {
-    for i in 0..allocations.len() {
-        for j in 0..allocations.len() {
-            if i == j {
-                continue;
-            }
-
-            if (allocations[i] - allocations[j]).abs() < CLEANING_TIME {
-                return false;
-            }
-        }
-    }
-
-    true
 }
 
 fn gen(seed: u64) -> Vec<Period> {
@@ -412,10 +272,7 @@ fn gen(seed: u64) -> Vec<Period> {
 
         let start = rng.gen_range(0..100_000);
 
-        occs.push(Period {
-            start,
-            end: start + len,
-        });
+        occs.push(Period::new(start, start + len));
     }
 
     occs
@@ -515,3 +372,203 @@ fn has_colouring_conflicts(graph: &Graph) -> bool {
 
     false
 }
+
+/// Parses the optional lines after the rooms. Each is the guest whose room is
+/// cleaned before they arrive, the staff member cleaning it and the second
+/// they start.
+fn parse_schedule<'a>(
+    lines: impl Iterator<Item = &'a str>,
+) -> Result<Vec<(OccupancyId, Cleaning)>, String> {
+    let mut schedule = vec![];
+
+    // The rooms are on the first line.
+    for (line_number, line) in lines.enumerate().map(|(i, l)| (i + 2, l)) {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let fields = line.split(',').map(|f| f.trim()).collect::<Vec<&str>>();
+
+        let expected = || {
+            format!(
+                "Line {}: expected a guest, a staff member and a start time.",
+                line_number
+            )
+        };
+
+        if fields.len() != 3 {
+            return Err(expected());
+        }
+
+        let guest = fields[0].parse::<OccupancyId>().map_err(|_| expected())?;
+        let staff = fields[1].parse::<usize>().map_err(|_| expected())?;
+        let start = fields[2].parse::<Second>().map_err(|_| expected())?;
+
+        if start.checked_add(CLEANING_TIME).is_none() {
+            return Err(format!(
+                "Line {}: a cleaning starting at {} would never finish.",
+                line_number, start
+            ));
+        }
+
+        schedule.push((guest, Cleaning { staff, start }));
+    }
+
+    Ok(schedule)
+}
+
+/// Checks a schedule given with the answer cleans each room once between
+/// every pair of guests in it, and never has anyone cleaning two rooms at
+/// once. Reports the first problem in the order given, or the earliest
+/// clash.
+fn check_schedule(graph: &Graph, schedule: &[(OccupancyId, Cleaning)]) -> Result<(), String> {
+    let jobs = cleaning_jobs(graph);
+
+    let job_before = jobs
+        .iter()
+        .map(|j| (j.occupant, j))
+        .collect::<HashMap<OccupancyId, &Job>>();
+
+    let mut cleaned = HashSet::new();
+    let mut cleanings = vec![];
+
+    for &(guest, cleaning) in schedule {
+        let Some(job) = job_before.get(&guest) else {
+            return Err(match graph.get(guest) {
+                Some(occ) => format!(
+                    "Guest {} is the first in room {}, so it doesn't need cleaning before they arrive.",
+                    guest,
+                    occ.room.unwrap()
+                ),
+                None => format!("There is no guest {}.", guest),
+            });
+        };
+
+        if !cleaned.insert(guest) {
+            return Err(format!(
+                "Room {} is cleaned twice before guest {} arrives.",
+                job.room, guest
+            ));
+        }
+
+        if cleaning.staff >= STAFF_COUNT {
+            return Err(format!(
+                "There is no staff member {}, they are numbered from 0 to {}.",
+                cleaning.staff,
+                STAFF_COUNT - 1
+            ));
+        }
+
+        let window = job.avalible_time_to_complete;
+        let period = Period::new(cleaning.start, cleaning.start + CLEANING_TIME);
+
+        if !window.contains(&period) {
+            return Err(format!(
+                "Room {} is cleaned starting at {}, but must be cleaned between guest {} leaving at {} and guest {} arriving at {}.",
+                job.room, cleaning.start, job.prior_occupant, window.start, guest, window.end
+            ));
+        }
+
+        cleanings.push((period, cleaning.staff, guest));
+    }
+
+    if let Some(job) = jobs.iter().find(|j| !cleaned.contains(&j.occupant)) {
+        return Err(format!(
+            "Room {} is not cleaned between guest {} leaving and guest {} arriving.",
+            job.room, job.prior_occupant, job.occupant
+        ));
+    }
+
+    // Every cleaning takes as long, so one that clashes with any earlier
+    // cleaning by the same person clashes with the one they started last.
+    cleanings.sort_by_key(|&(period, staff, _)| (period.start, staff));
+
+    let mut last_cleaning: HashMap<usize, (Period, OccupancyId)> = HashMap::new();
+
+    for (period, staff, guest) in cleanings {
+        if let Some((last, last_guest)) = last_cleaning.insert(staff, (period, guest)) {
+            if last.overlaps(&period) {
+                return Err(format!(
+                    "Staff member {} is still cleaning the room for guest {} until {} when they start on the room for guest {} at {}.",
+                    staff, last_guest, last.end, guest, period.start
+                ));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn schedule_rejects_cleaning_that_never_finishes() {
+        let lines = ["0, 0, 100", "1, 0, 9223372036854775807"];
+
+        assert_eq!(
+            parse_schedule(lines.into_iter()),
+            Err(
+                "Line 3: a cleaning starting at 9223372036854775807 would never finish."
+                    .to_string()
+            )
+        );
+    }
+
+    /// Builds graphs for random stays and checks each pair of stays is joined
+    /// exactly when neither stay ends at least CLEANING_TIME seconds before
+    /// the other begins.
+    #[test]
+    fn conflict_graph_matches_timeline() {
+        const TIMELINE: Second = 10_000;
+
+        let mut rng = ChaChaRng::seed_from_u64(0);
+
+        for _ in 0..50 {
+            let stays = (0..20)
+                .map(|_| {
+                    let start = rng.gen_range(0..TIMELINE - 3_000);
+                    Period::new(start, start + rng.gen_range(1..3_000))
+                })
+                .collect::<Vec<Period>>();
+
+            let graph = create_graph(&stays);
+
+            // The seconds each stay takes up on the timeline.
+            let seconds = stays
+                .iter()
+                .map(|p| {
+                    (0..TIMELINE)
+                        .filter(|s| p.start <= *s && *s < p.end)
+                        .collect()
+                })
+                .collect::<Vec<Vec<Second>>>();
+
+            let cleanable_between = |first: usize, second: usize| {
+                let last = *seconds[first].last().unwrap();
+                let next = seconds[second][0];
+
+                next - (last + 1) >= CLEANING_TIME
+            };
+
+            for i in 0..stays.len() {
+                for j in 0..stays.len() {
+                    if i == j {
+                        continue;
+                    }
+
+                    let conflict = !cleanable_between(i, j) && !cleanable_between(j, i);
+
+                    assert_eq!(
+                        graph[i].neighbours.contains(&j),
+                        conflict,
+                        "{:?} and {:?}",
+                        stays[i],
+                        stays[j]
+                    );
+                }
+            }
+        }
+    }
+}
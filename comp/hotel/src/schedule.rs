@@ -0,0 +1,277 @@
+//! Decides exactly whether the cleaning staff can clean every room in time.
+//!
+//! Each job takes CLEANING_TIME and has to be done within a window, from the
+//! departure of one guest to the arrival of the next guest in the room. Jobs
+//! are all the same length, so which staff member does which job doesn't
+//! matter: start times sorted in order can be worked by STAFF_COUNT staff
+//! exactly when each start is at least CLEANING_TIME after the start
+//! STAFF_COUNT places before it, by giving each job to whoever is free first.
+//!
+//! Any schedule can be shifted earlier until every start is either the
+//! earliest that rule allows or the start of some job's window. For a given
+//! set of start times, giving each one to the released job with the earliest
+//! deadline is optimal. So the search only has to decide, for each start in
+//! turn, whether to clean the most urgent room available now or wait for a
+//! more urgent one.
+
+use std::collections::HashSet;
+
+use crate::{interval::Period, Second, CLEANING_TIME};
+
+pub const STAFF_COUNT: usize = 5;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Cleaning {
+    pub staff: usize,
+    pub start: Second,
+}
+
+/// Finds a start time and staff member for the job in each window, or
+/// returns the index of the first job the search found couldn't be done in
+/// time, to blame for the schedule not working.
+pub fn schedule(windows: &[Period]) -> Result<Vec<Cleaning>, usize> {
+    let mut by_deadline = (0..windows.len()).collect::<Vec<usize>>();
+    by_deadline.sort_by_key(|&j| windows[j].end);
+
+    let mut search = Search {
+        windows,
+        by_deadline,
+        remaining: vec![true; windows.len()],
+        starts: Vec::new(),
+        failed: HashSet::new(),
+        culprit: None,
+    };
+
+    if !search.place_next() {
+        return Err(search.culprit.unwrap());
+    }
+
+    // Everyone who is free by the start has time for it, so give each job to
+    // whoever has been free longest.
+    let mut free_from = [Second::MIN; STAFF_COUNT];
+    let mut cleanings = vec![Cleaning { staff: 0, start: 0 }; windows.len()];
+
+    for &(job, start) in search.starts.iter() {
+        let staff = (0..STAFF_COUNT).min_by_key(|&s| free_from[s]).unwrap();
+        assert!(free_from[staff] <= start);
+
+        free_from[staff] = start + CLEANING_TIME;
+        cleanings[job] = Cleaning { staff, start };
+    }
+
+    Ok(cleanings)
+}
+
+struct Search<'a> {
+    windows: &'a [Period],
+    /// Jobs in order of deadline, so the first released one is the most
+    /// urgent.
+    by_deadline: Vec<usize>,
+    remaining: Vec<bool>,
+    /// Jobs and their start times so far, in order of start time.
+    starts: Vec<(usize, Second)>,
+    /// Jobs left and the latest start times that are known not to work.
+    failed: HashSet<(Vec<bool>, Vec<Second>)>,
+    culprit: Option<usize>,
+}
+
+impl Search<'_> {
+    fn latest_start(&self, job: usize) -> Second {
+        self.windows[job].end - CLEANING_TIME
+    }
+
+    fn place_next(&mut self) -> bool {
+        if self.starts.len() == self.windows.len() {
+            return true;
+        }
+
+        // Only the last STAFF_COUNT starts limit what comes next.
+        let recent = self.starts[self.starts.len().saturating_sub(STAFF_COUNT)..]
+            .iter()
+            .map(|&(_, start)| start)
+            .collect::<Vec<Second>>();
+
+        let key = (self.remaining.clone(), recent);
+        if self.failed.contains(&key) {
+            return false;
+        }
+
+        let mut earliest = self.starts.last().map_or(Second::MIN, |&(_, start)| start);
+        if self.starts.len() >= STAFF_COUNT {
+            earliest = earliest.max(self.starts[self.starts.len() - STAFF_COUNT].1 + CLEANING_TIME);
+        }
+
+        // Even if every job left could start as early as the staff allow, the
+        // one with the nth earliest deadline can't start before the nth start.
+        let mut next_starts = key.1.clone();
+        let mut next = earliest;
+
+        for &job in self.by_deadline.iter().filter(|&&j| self.remaining[j]) {
+            if self.latest_start(job) < next {
+                self.culprit.get_or_insert(job);
+                self.failed.insert(key);
+                return false;
+            }
+
+            next_starts.push(next);
+            if next_starts.len() >= STAFF_COUNT {
+                next = next.max(next_starts[next_starts.len() - STAFF_COUNT] + CLEANING_TIME);
+            }
+        }
+
+        let mut times = (0..self.windows.len())
+            .filter(|&j| self.remaining[j] && self.windows[j].start > earliest)
+            .map(|j| self.windows[j].start)
+            .collect::<Vec<Second>>();
+        times.push(earliest);
+        times.sort();
+        times.dedup();
+
+        let mut tried = Vec::new();
+
+        for start in times {
+            // Jobs only start later from here on, so a job that can't start
+            // now never will.
+            if let Some(late) =
+                (0..self.windows.len()).find(|&j| self.remaining[j] && self.latest_start(j) < start)
+            {
+                self.culprit.get_or_insert(late);
+                break;
+            }
+
+            let Some(job) = self
+                .by_deadline
+                .iter()
+                .copied()
+                .find(|&j| self.remaining[j] && self.windows[j].start <= start)
+            else {
+                continue;
+            };
+
+            // Starting the same job later is never better.
+            if tried.contains(&job) {
+                continue;
+            }
+            tried.push(job);
+
+            self.remaining[job] = false;
+            self.starts.push((job, start));
+
+            if self.place_next() {
+                return true;
+            }
+
+            self.starts.pop();
+            self.remaining[job] = true;
+        }
+
+        self.failed.insert(key);
+
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaChaRng;
+
+    use super::*;
+
+    /// Compares `schedule` against trying every order of the jobs for random
+    /// small sets of windows, and checks every schedule it finds keeps each
+    /// job in its window and gives nobody two jobs at once.
+    #[test]
+    fn matches_every_order() {
+        const TIMELINE: Second = 2 * CLEANING_TIME;
+
+        let mut rng = ChaChaRng::seed_from_u64(0);
+
+        for _ in 0..2_000 {
+            let windows = (0..rng.gen_range(1..=9))
+                .map(|_| {
+                    let start = rng.gen_range(0..TIMELINE);
+                    Period::new(
+                        start,
+                        start + rng.gen_range(CLEANING_TIME..2 * CLEANING_TIME),
+                    )
+                })
+                .collect::<Vec<Period>>();
+
+            let feasible = fits_in_some_order(
+                &windows,
+                [Second::MIN; STAFF_COUNT],
+                &mut vec![false; windows.len()],
+            );
+
+            let Ok(cleanings) = schedule(&windows) else {
+                assert!(!feasible, "{:?} should be cleanable.", windows);
+                continue;
+            };
+
+            assert!(feasible, "{:?} should not be cleanable.", windows);
+
+            for (i, (a, window)) in cleanings.iter().zip(&windows).enumerate() {
+                let job = Period::new(a.start, a.start + CLEANING_TIME);
+
+                assert!(
+                    window.contains(&job),
+                    "{:?}: job {} is cleaned outside its window.",
+                    windows,
+                    i
+                );
+
+                for (j, b) in cleanings.iter().enumerate().skip(i + 1) {
+                    let other = Period::new(b.start, b.start + CLEANING_TIME);
+
+                    assert!(
+                        a.staff != b.staff || !job.overlaps(&other),
+                        "{:?}: jobs {} and {} are cleaned by the same person at once.",
+                        windows,
+                        i,
+                        j
+                    );
+                }
+            }
+        }
+    }
+
+    /// Tries every order of the jobs, giving each in turn to whoever is free
+    /// first, as early as it can start. Some order does this for every
+    /// schedule that works.
+    fn fits_in_some_order(
+        windows: &[Period],
+        free_from: [Second; STAFF_COUNT],
+        done: &mut Vec<bool>,
+    ) -> bool {
+        if done.iter().all(|&d| d) {
+            return true;
+        }
+
+        for job in 0..windows.len() {
+            if done[job] {
+                continue;
+            }
+
+            let mut free_from = free_from;
+            let staff = (0..STAFF_COUNT).min_by_key(|&s| free_from[s]).unwrap();
+            let start = free_from[staff].max(windows[job].start);
+
+            free_from[staff] = start + CLEANING_TIME;
+
+            if free_from[staff] > windows[job].end {
+                continue;
+            }
+
+            done[job] = true;
+
+            if fits_in_some_order(windows, free_from, done) {
+                return true;
+            }
+
+            done[job] = false;
+        }
+
+        false
+    }
+}
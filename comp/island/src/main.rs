@@ -3,7 +3,9 @@ type Terrain = Vec<Vec<Elevation>>;
 
 use std::{
     collections::hash_map::DefaultHasher,
+    fs::File,
     hash::{Hash, Hasher},
+    io::BufWriter,
     process::exit,
 };
 
@@ -30,6 +32,10 @@ fn main() {
     match args[1].as_str() {
         "generate" => {
             print_terrain(&terrain);
+
+            if let Ok(path) = std::env::var("ISLAND_PNG") {
+                write_heightmap(&terrain, &path).expect("Failed to write heightmap.");
+            }
         }
         "validate" => {
             let mut buffer = String::new();
@@ -87,6 +93,34 @@ fn print_terrain(terrain: &Terrain) {
     }
 }
 
+/// Writes the terrain as a greyscale PNG with the lowest point black and the
+/// highest point white, so the problem page can show what the islands look like.
+fn write_heightmap(terrain: &Terrain, path: &str) -> Result<(), png::EncodingError> {
+    let elevations = terrain.iter().flatten();
+    let lowest = elevations.clone().copied().fold(f64::INFINITY, f64::min);
+    let highest = elevations
+        .clone()
+        .copied()
+        .fold(f64::NEG_INFINITY, f64::max);
+    let range = (highest - lowest).max(f64::EPSILON);
+
+    let data = elevations
+        .map(|elevation| ((elevation - lowest) / range * 255.0).round() as u8)
+        .collect::<Vec<u8>>();
+
+    let file = File::create(path)?;
+    let mut encoder = png::Encoder::new(
+        BufWriter::new(file),
+        terrain[0].len() as u32,
+        terrain.len() as u32,
+    );
+    encoder.set_color(png::ColorType::Grayscale);
+    encoder.set_depth(png::BitDepth::Eight);
+
+    let mut writer = encoder.write_header()?;
+    writer.write_image_data(&data)
+}
+
 fn blank_terrain(width: usize, height: usize) -> Terrain {
     vec![vec![0.0; width]; height]
 }
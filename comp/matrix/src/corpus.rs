@@ -0,0 +1,92 @@
+//! Chooses the words hidden in the code.
+
+use std::ops::RangeInclusive;
+
+use rand::{distributions::WeightedIndex, prelude::Distribution, Rng};
+
+const LOREM_IPSUM: &str = include_str!("../words/lorem.txt");
+const ENGLISH: &str = include_str!("../words/english.txt");
+const ANIMALS: &str = include_str!("../words/animals.txt");
+
+/// How long words are favoured when none is set with `MATRIX_LENGTH_BIAS`.
+/// Each seed picks a bias in this range, so the average length varies
+/// between seeds rather than always being close to the corpus average.
+const DEFAULT_BIAS: RangeInclusive<f64> = -2.0..=2.0;
+
+pub struct WordSource {
+    corpus: Vec<String>,
+    count: RangeInclusive<usize>,
+    bias: Option<f64>,
+}
+
+impl WordSource {
+    /// The corpus is one of the bundled lists named by `MATRIX_CORPUS`, or
+    /// the words in the file at `MATRIX_WORDS_FILE`. Words longer than
+    /// `max_length` or outside `MATRIX_WORD_LENGTH` are left out.
+    pub fn from_env(max_length: usize) -> WordSource {
+        let text = match std::env::var("MATRIX_WORDS_FILE") {
+            Ok(path) => std::fs::read_to_string(&path)
+                .unwrap_or_else(|e| panic!("Could not read words from {}: {}", path, e)),
+            Err(_) => match std::env::var("MATRIX_CORPUS").as_deref() {
+                Err(_) | Ok("lorem") => LOREM_IPSUM,
+                Ok("english") => ENGLISH,
+                Ok("animals") => ANIMALS,
+                Ok(other) => panic!("Unknown matrix corpus: {}", other),
+            }
+            .to_string(),
+        };
+
+        let length = range_from_env("MATRIX_WORD_LENGTH").unwrap_or(1..=max_length);
+
+        let corpus = text
+            .split_whitespace()
+            .map(|s| s.chars().filter(|c| c.is_alphabetic()).collect::<String>())
+            .filter(|s| s.len() <= max_length && length.contains(&s.len()))
+            .collect::<Vec<String>>();
+
+        assert!(!corpus.is_empty(), "No words in the corpus fit the grid.");
+
+        let count = range_from_env("MATRIX_WORD_COUNT").unwrap_or(60..=100);
+
+        let bias = std::env::var("MATRIX_LENGTH_BIAS").ok().map(|bias| {
+            bias.parse::<f64>()
+                .expect("MATRIX_LENGTH_BIAS should be a number.")
+        });
+
+        WordSource {
+            corpus,
+            count,
+            bias,
+        }
+    }
+
+    /// Draws words from the corpus with replacement. Each word is weighted by
+    /// its length raised to the bias, so a positive bias favours long words
+    /// and a negative one short words.
+    pub fn choose<R: Rng>(&self, rng: &mut R) -> Vec<String> {
+        let count = rng.gen_range(self.count.clone());
+        let bias = self.bias.unwrap_or_else(|| rng.gen_range(DEFAULT_BIAS));
+
+        let weights = self.corpus.iter().map(|s| (s.len() as f64).powf(bias));
+        let distribution = WeightedIndex::new(weights).unwrap();
+
+        (0..count)
+            .map(|_| self.corpus[distribution.sample(rng)].clone())
+            .collect()
+    }
+}
+
+/// Reads either a single number or an inclusive range written `min-max`.
+fn range_from_env(name: &str) -> Option<RangeInclusive<usize>> {
+    let range = std::env::var(name).ok()?;
+
+    let bounds = match range.split_once('-') {
+        Some((min, max)) => min.trim().parse().ok().zip(max.trim().parse().ok()),
+        None => range.trim().parse().ok().map(|n| (n, n)),
+    };
+
+    match bounds {
+        Some((min, max)) if min >= 1 && min <= max => Some(min..=max),
+        _ => panic!("{} should be a number or a range like `60-100`.", name),
+    }
+}
@@ -0,0 +1,336 @@
+//! Places words in the grid in any of the enabled directions.
+
+use std::{fmt, ops::Range};
+
+use rand::{seq::SliceRandom, Rng};
+
+/// Every direction reads left to right, or top to bottom when vertical, so
+/// each line of letters has exactly one reading.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Down,
+    Right,
+    DownRight,
+    UpRight,
+}
+
+impl Direction {
+    /// The change in x and y from one letter to the next.
+    fn step(self) -> (isize, isize) {
+        match self {
+            Direction::Down => (0, 1),
+            Direction::Right => (1, 0),
+            Direction::DownRight => (1, 1),
+            Direction::UpRight => (1, -1),
+        }
+    }
+}
+
+/// Selected with the `MATRIX_DIRECTIONS` and `MATRIX_CROSSINGS` environment
+/// variables.
+pub struct Rules {
+    pub directions: Vec<Direction>,
+    /// Whether words in different directions can share a letter where they
+    /// cross, as in a word search.
+    pub crossings: bool,
+}
+
+impl Rules {
+    pub fn from_env() -> Rules {
+        let directions = match std::env::var("MATRIX_DIRECTIONS") {
+            Err(_) => vec![Direction::Down],
+            Ok(directions) => directions
+                .split(',')
+                .flat_map(|direction| match direction.trim() {
+                    "down" => vec![Direction::Down],
+                    "right" => vec![Direction::Right],
+                    "diagonal" => vec![Direction::DownRight, Direction::UpRight],
+                    "all" => vec![
+                        Direction::Down,
+                        Direction::Right,
+                        Direction::DownRight,
+                        Direction::UpRight,
+                    ],
+                    other => panic!("Unknown matrix direction: {}", other),
+                })
+                .collect(),
+        };
+
+        let crossings = std::env::var("MATRIX_CROSSINGS").as_deref() == Ok("1");
+
+        Rules {
+            directions,
+            crossings,
+        }
+    }
+
+    /// The length of the longest word that fits in an empty grid.
+    pub fn longest_word(&self, width: usize, height: usize) -> usize {
+        self.directions
+            .iter()
+            .map(|direction| match direction {
+                Direction::Down => height,
+                Direction::Right => width,
+                Direction::DownRight | Direction::UpRight => width.min(height),
+            })
+            .max()
+            .unwrap()
+    }
+}
+
+/// A letter and the indices of the words written through it.
+#[derive(Clone, Default)]
+struct Cell {
+    letter: Option<char>,
+    words: Vec<usize>,
+}
+
+/// Returned when a word can't be placed anywhere in the grid.
+#[derive(Debug)]
+pub struct NoRoom {
+    pub word: String,
+}
+
+impl fmt::Display for NoRoom {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Could not find a place to put `{}`.", self.word)
+    }
+}
+
+pub struct Grid {
+    width: usize,
+    height: usize,
+    cells: Vec<Vec<Cell>>,
+    directions: Vec<Direction>,
+    /// The runs of empty cells down each column, as `start..end` rows.
+    free_runs: Vec<Vec<Range<usize>>>,
+}
+
+impl Grid {
+    pub fn new(width: usize, height: usize) -> Grid {
+        Grid {
+            width,
+            height,
+            cells: vec![vec![Cell::default(); width]; height],
+            directions: Vec::new(),
+            free_runs: vec![vec![0..height]; width],
+        }
+    }
+
+    /// Tries each enabled direction in a random order.
+    pub fn place<R: Rng>(&mut self, word: &str, rules: &Rules, rng: &mut R) -> Result<(), NoRoom> {
+        let chars = word.chars().collect::<Vec<char>>();
+
+        let mut directions = rules.directions.clone();
+        directions.shuffle(rng);
+
+        for direction in directions {
+            if let Some(cells) = self.find_place(&chars, direction, rules, rng) {
+                self.write(&chars, &cells, direction);
+                return Ok(());
+            }
+        }
+
+        Err(NoRoom {
+            word: word.to_string(),
+        })
+    }
+
+    /// Tries random starting positions first so words spread out over the
+    /// grid, then falls back to checking every starting position.
+    ///
+    /// Without crossings, vertical words only start where the free runs have
+    /// room for them, so with only vertical words the first position always
+    /// fits. With crossings they can also run through other words, so every
+    /// position is checked like the other directions.
+    fn find_place<R: Rng>(
+        &self,
+        word: &[char],
+        direction: Direction,
+        rules: &Rules,
+        rng: &mut R,
+    ) -> Option<Vec<(usize, usize)>> {
+        const RANDOM_ATTEMPTS: usize = 100;
+
+        let starts = match direction {
+            Direction::Down if !rules.crossings => self.vertical_starts(word.len()),
+            _ => (0..self.width)
+                .flat_map(|x| (0..self.height).map(move |y| (x, y)))
+                .collect(),
+        };
+
+        if starts.is_empty() {
+            return None;
+        }
+
+        for _ in 0..RANDOM_ATTEMPTS {
+            let (x, y) = starts[rng.gen_range(0..starts.len())];
+
+            if let Some(cells) = self.fits(word, x, y, direction, rules) {
+                return Some(cells);
+            }
+        }
+
+        starts
+            .into_iter()
+            .find_map(|(x, y)| self.fits(word, x, y, direction, rules))
+    }
+
+    /// Where a vertical word of length `len` can start within a free run,
+    /// leaving an empty cell above and below it unless it's against the edge
+    /// of the grid.
+    fn vertical_starts(&self, len: usize) -> Vec<(usize, usize)> {
+        let mut starts = Vec::new();
+
+        for (x, runs) in self.free_runs.iter().enumerate() {
+            for run in runs {
+                let top = if run.start == 0 { 0 } else { run.start + 1 };
+                let bottom = if run.end == self.height {
+                    run.end
+                } else {
+                    run.end - 1
+                };
+
+                if bottom >= top + len {
+                    starts.extend((top..=bottom - len).map(|y| (x, y)));
+                }
+            }
+        }
+
+        starts
+    }
+
+    /// The cells the word would cover if it can be written starting at
+    /// `(x, y)`.
+    ///
+    /// The cells either side of the word along its direction must be empty
+    /// so it can't be read as part of a longer word. When more than one
+    /// direction is enabled, words also can't touch any other word, except
+    /// for the words they cross, so that every word can be traced.
+    fn fits(
+        &self,
+        word: &[char],
+        x: usize,
+        y: usize,
+        direction: Direction,
+        rules: &Rules,
+    ) -> Option<Vec<(usize, usize)>> {
+        let cells = (0..word.len())
+            .map(|i| self.offset(x, y, direction, i as isize))
+            .collect::<Option<Vec<(usize, usize)>>>()?;
+
+        let before = self.offset(x, y, direction, -1);
+        let after = self.offset(x, y, direction, word.len() as isize);
+
+        if [before, after]
+            .into_iter()
+            .flatten()
+            .any(|(x, y)| self.cells[y][x].letter.is_some())
+        {
+            return None;
+        }
+
+        let mut crossed = Vec::new();
+
+        for (&(x, y), &c) in cells.iter().zip(word) {
+            let cell = &self.cells[y][x];
+
+            if let Some(letter) = cell.letter {
+                let crossable = rules.crossings
+                    && letter == c
+                    && cell.words.len() == 1
+                    && self.directions[cell.words[0]] != direction;
+
+                if !crossable {
+                    return None;
+                }
+
+                crossed.push(cell.words[0]);
+            }
+        }
+
+        if rules.directions.len() > 1 {
+            for &(x, y) in cells.iter() {
+                for (nx, ny) in self.neighbours(x, y) {
+                    if cells.contains(&(nx, ny)) {
+                        continue;
+                    }
+
+                    if !self.cells[ny][nx].words.iter().all(|w| crossed.contains(w)) {
+                        return None;
+                    }
+                }
+            }
+        }
+
+        Some(cells)
+    }
+
+    fn write(&mut self, word: &[char], cells: &[(usize, usize)], direction: Direction) {
+        let index = self.directions.len();
+        self.directions.push(direction);
+
+        for (&(x, y), &c) in cells.iter().zip(word) {
+            if self.cells[y][x].letter.is_none() {
+                self.take_free_cell(x, y);
+            }
+
+            self.cells[y][x].letter = Some(c);
+            self.cells[y][x].words.push(index);
+        }
+    }
+
+    /// Splits the free run containing the cell around it.
+    fn take_free_cell(&mut self, x: usize, y: usize) {
+        let runs = &mut self.free_runs[x];
+        let i = runs.iter().position(|run| run.contains(&y)).unwrap();
+        let run = runs.remove(i);
+
+        let split = [run.start..y, y + 1..run.end];
+        for (j, part) in split
+            .into_iter()
+            .filter(|part| !part.is_empty())
+            .enumerate()
+        {
+            runs.insert(i + j, part);
+        }
+    }
+
+    /// The position `distance` letters along from `(x, y)`, if it's inside
+    /// the grid.
+    fn offset(
+        &self,
+        x: usize,
+        y: usize,
+        direction: Direction,
+        distance: isize,
+    ) -> Option<(usize, usize)> {
+        let (dx, dy) = direction.step();
+
+        let x = x.checked_add_signed(dx * distance)?;
+        let y = y.checked_add_signed(dy * distance)?;
+
+        (x < self.width && y < self.height).then_some((x, y))
+    }
+
+    fn neighbours(&self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        (-1..=1)
+            .flat_map(|dy| (-1..=1).map(move |dx| (dx, dy)))
+            .filter(|&d| d != (0, 0))
+            .filter_map(move |(dx, dy)| {
+                let x = x.checked_add_signed(dx)?;
+                let y = y.checked_add_signed(dy)?;
+
+                (x < self.width && y < self.height).then_some((x, y))
+            })
+    }
+
+    pub fn print(&self) {
+        for row in self.cells.iter() {
+            for cell in row {
+                print!("{}", cell.letter.unwrap_or(' '));
+            }
+            println!();
+        }
+    }
+}
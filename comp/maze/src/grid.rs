@@ -0,0 +1,319 @@
+use std::{fs::File, io::BufWriter};
+
+use rand::Rng;
+
+use crate::Maze;
+
+/// A room in the grid, addressed as (x, y).
+type Room = (usize, usize);
+
+/// Size in pixels of each block when the grid is rendered to an image.
+const PNG_SCALE: usize = 8;
+
+#[derive(Clone, Copy, PartialEq)]
+enum Owner {
+    /// Solid rock that isn't part of the maze.
+    Rock,
+    /// The node at this index of the correct route, or a web node hung off it.
+    Route(usize),
+    Web(usize),
+}
+
+impl Owner {
+    fn route_index(self) -> Option<usize> {
+        match self {
+            Owner::Rock => None,
+            Owner::Route(i) | Owner::Web(i) => Some(i),
+        }
+    }
+}
+
+/// A rectangular grid of rooms separated by walls. Only the correct route is
+/// kept from the maze, one room for each of its nodes, while the dead ends
+/// around it are new.
+pub struct Grid {
+    width: usize,
+    height: usize,
+    owners: Vec<Vec<Owner>>,
+    /// Whether there is an opening from a room to the room on its right.
+    open_right: Vec<Vec<bool>>,
+    /// Whether there is an opening from a room to the room below it.
+    open_down: Vec<Vec<bool>>,
+    start: Room,
+    end: Room,
+}
+
+impl Grid {
+    fn new(width: usize, height: usize) -> Grid {
+        Grid {
+            width,
+            height,
+            owners: vec![vec![Owner::Rock; width]; height],
+            open_right: vec![vec![false; width]; height],
+            open_down: vec![vec![false; width]; height],
+            start: (0, 0),
+            end: (0, 0),
+        }
+    }
+
+    fn owner(&self, (x, y): Room) -> Owner {
+        self.owners[y][x]
+    }
+
+    fn neighbours(&self, (x, y): Room) -> Vec<Room> {
+        let mut rooms = vec![];
+        if x > 0 {
+            rooms.push((x - 1, y));
+        }
+        if x + 1 < self.width {
+            rooms.push((x + 1, y));
+        }
+        if y > 0 {
+            rooms.push((x, y - 1));
+        }
+        if y + 1 < self.height {
+            rooms.push((x, y + 1));
+        }
+        rooms
+    }
+
+    fn is_open(&self, a: Room, b: Room) -> bool {
+        let ((x, y), other) = if a < b { (a, b) } else { (b, a) };
+        if other == (x + 1, y) {
+            self.open_right[y][x]
+        } else {
+            self.open_down[y][x]
+        }
+    }
+
+    /// Knocks down the wall between two adjacent rooms.
+    fn open(&mut self, a: Room, b: Room) {
+        let ((x, y), other) = if a < b { (a, b) } else { (b, a) };
+        if other == (x + 1, y) {
+            self.open_right[y][x] = true;
+        } else {
+            assert_eq!(other, (x, y + 1));
+            self.open_down[y][x] = true;
+        }
+    }
+
+    /// Prints the grid using `+`, `-` and `|` for walls. Rooms on the maze are
+    /// blank except for the start (`S`) and end (`E`), rock is filled with `#`.
+    pub fn print(&self) {
+        for y in 0..self.height {
+            let mut walls = String::new();
+            let mut rooms = String::new();
+
+            for x in 0..self.width {
+                walls.push('+');
+                if y > 0 && self.is_open((x, y - 1), (x, y)) {
+                    walls.push_str("   ");
+                } else {
+                    walls.push_str("---");
+                }
+
+                if x > 0 && self.is_open((x - 1, y), (x, y)) {
+                    rooms.push(' ');
+                } else {
+                    rooms.push('|');
+                }
+                rooms.push_str(match ((x, y), self.owner((x, y))) {
+                    (room, _) if room == self.start => " S ",
+                    (room, _) if room == self.end => " E ",
+                    (_, Owner::Rock) => "###",
+                    _ => "   ",
+                });
+            }
+
+            walls.push('+');
+            rooms.push('|');
+
+            println!("{}", walls);
+            println!("{}", rooms);
+        }
+
+        println!("{}+", "+---".repeat(self.width));
+    }
+
+    /// Renders the grid as an RGB image with thick walls, the start in green
+    /// and the end in red.
+    pub fn write_png(&self, path: &str) -> Result<(), png::EncodingError> {
+        const WALL: [u8; 3] = [0, 0, 0];
+        const ROCK: [u8; 3] = [96, 96, 96];
+        const FLOOR: [u8; 3] = [255, 255, 255];
+        const START: [u8; 3] = [0, 200, 0];
+        const END: [u8; 3] = [220, 0, 0];
+
+        let blocks_wide = self.width * 2 + 1;
+        let blocks_high = self.height * 2 + 1;
+
+        let mut blocks = vec![vec![WALL; blocks_wide]; blocks_high];
+
+        for y in 0..self.height {
+            for x in 0..self.width {
+                blocks[y * 2 + 1][x * 2 + 1] = match self.owner((x, y)) {
+                    _ if (x, y) == self.start => START,
+                    _ if (x, y) == self.end => END,
+                    Owner::Rock => ROCK,
+                    _ => FLOOR,
+                };
+
+                if self.open_right[y][x] {
+                    blocks[y * 2 + 1][x * 2 + 2] = FLOOR;
+                }
+                if self.open_down[y][x] {
+                    blocks[y * 2 + 2][x * 2 + 1] = FLOOR;
+                }
+            }
+        }
+
+        let mut data = Vec::with_capacity(blocks_wide * blocks_high * PNG_SCALE * PNG_SCALE * 3);
+        for row in &blocks {
+            for _ in 0..PNG_SCALE {
+                for block in row {
+                    for _ in 0..PNG_SCALE {
+                        data.extend_from_slice(block);
+                    }
+                }
+            }
+        }
+
+        let file = File::create(path)?;
+        let mut encoder = png::Encoder::new(
+            BufWriter::new(file),
+            (blocks_wide * PNG_SCALE) as u32,
+            (blocks_high * PNG_SCALE) as u32,
+        );
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+
+        let mut writer = encoder.write_header()?;
+        writer.write_image_data(&data)
+    }
+}
+
+/// Lays out the maze's route on a grid of rooms. The correct route becomes a
+/// self-avoiding walk through the grid. Webs can have nodes with more than
+/// four corridors, so they can't be drawn as they are; instead each one is
+/// replaced by a new region of up to as many rooms, grown around the route
+/// room it hangs off. Walls are only ever opened between consecutive route
+/// rooms or rooms of the same region, so just like the graph there is
+/// exactly one route from start to end and it passes through the same number
+/// of rooms as the graph's route has nodes.
+pub fn embed_maze<R: Rng>(maze: &Maze, rng: &mut R) -> Grid {
+    let route_length = maze.web_sizes.len();
+    let node_count = route_length + maze.web_sizes.iter().sum::<usize>();
+
+    let side = (node_count as f64).sqrt().ceil() as usize + 2;
+
+    let mut grid = Grid::new(side, side);
+
+    let start = (
+        rng.gen_range(side / 4..side * 3 / 4),
+        rng.gen_range(side / 4..side * 3 / 4),
+    );
+    let mut route = vec![start];
+    let mut visited = vec![vec![false; side]; side];
+    visited[start.1][start.0] = true;
+
+    assert!(
+        walk(&grid, &mut route, &mut visited, route_length, rng),
+        "Could not lay out the route through the grid."
+    );
+
+    for (i, &room) in route.iter().enumerate() {
+        grid.owners[room.1][room.0] = Owner::Route(i);
+        if i > 0 {
+            grid.open(route[i - 1], room);
+        }
+    }
+
+    grid.start = route[0];
+    grid.end = *route.last().unwrap();
+
+    grow_webs(&mut grid, &route, &maze.web_sizes, rng);
+    add_loops(&mut grid, rng);
+
+    grid
+}
+
+/// Randomised depth first search for a self-avoiding walk of `length` rooms.
+fn walk<R: Rng>(
+    grid: &Grid,
+    route: &mut Vec<Room>,
+    visited: &mut Vec<Vec<bool>>,
+    length: usize,
+    rng: &mut R,
+) -> bool {
+    if route.len() == length {
+        return true;
+    }
+
+    let mut options = grid.neighbours(*route.last().unwrap());
+    options.retain(|&(x, y)| !visited[y][x]);
+
+    while !options.is_empty() {
+        let (x, y) = options.swap_remove(rng.gen_range(0..options.len()));
+
+        visited[y][x] = true;
+        route.push((x, y));
+
+        if walk(grid, route, visited, length, rng) {
+            return true;
+        }
+
+        route.pop();
+        visited[y][x] = false;
+    }
+
+    false
+}
+
+/// Grows each web outwards from its route room as a random spanning tree
+/// until it has as many rooms as the web has nodes or runs out of space.
+fn grow_webs<R: Rng>(grid: &mut Grid, route: &[Room], web_sizes: &[usize], rng: &mut R) {
+    let mut remaining = web_sizes.to_vec();
+    let mut active = route.to_vec();
+
+    while !active.is_empty() {
+        let index = rng.gen_range(0..active.len());
+        let room = active[index];
+        let owner = grid.owner(room).route_index().unwrap();
+
+        let mut options = grid.neighbours(room);
+        options.retain(|&r| grid.owner(r) == Owner::Rock);
+
+        if remaining[owner] == 0 || options.is_empty() {
+            active.swap_remove(index);
+            continue;
+        }
+
+        let next = options[rng.gen_range(0..options.len())];
+        grid.owners[next.1][next.0] = Owner::Web(owner);
+        grid.open(room, next);
+        remaining[owner] -= 1;
+        active.push(next);
+    }
+}
+
+/// Webs in the graph can contain cycles, so open a few extra walls between
+/// rooms of the same region to give the grid some too. These never join two
+/// different route rooms.
+fn add_loops<R: Rng>(grid: &mut Grid, rng: &mut R) {
+    for y in 0..grid.height {
+        for x in 0..grid.width {
+            for other in [(x + 1, y), (x, y + 1)] {
+                if other.0 >= grid.width || other.1 >= grid.height {
+                    continue;
+                }
+
+                let (a, b) = (grid.owner((x, y)), grid.owner(other));
+                let same_web = matches!((a, b), (Owner::Web(i), Owner::Web(j)) if i == j);
+
+                if same_web && !grid.is_open((x, y), other) && rng.gen_bool(0.1) {
+                    grid.open((x, y), other);
+                }
+            }
+        }
+    }
+}
@@ -7,7 +7,12 @@ use std::{
 
 use rand::{Rng, SeedableRng};
 
+use grid::embed_maze;
 use rand_chacha::ChaChaRng;
+use solve::{count_shortest_paths, shortest_distance};
+
+mod grid;
+mod solve;
 
 fn main() {
     let args = std::env::args().collect::<Vec<String>>();
@@ -18,13 +23,49 @@ fn main() {
     args[2].hash(&mut s);
     let seed = s.finish();
 
-    let mut rng = ChaChaRng::seed_from_u64(seed);
+    let variant = Variant::from_env();
 
-    let length = rng.gen_range(20..=30);
+    let (maze, mut rng) = generate_maze(seed, variant);
 
     match args[1].as_str() {
-        "generate" => {
-            create_maze(length, &mut rng);
+        "generate" => match std::env::var("MAZE_FORMAT").as_deref() {
+            Err(_) | Ok("graph") => {
+                print_graph(
+                    &maze.graph,
+                    Some(&maze.id_shuffle_map),
+                    maze.start,
+                    maze.end,
+                    variant,
+                );
+            }
+            Ok("grid") => {
+                assert!(
+                    !variant.weighted && !variant.directed,
+                    "Grid mazes only support the plain variant."
+                );
+
+                let grid = embed_maze(&maze, &mut rng);
+                grid.print();
+
+                if let Ok(path) = std::env::var("MAZE_PNG") {
+                    grid.write_png(&path).expect("Failed to write maze image.");
+                }
+            }
+            Ok(other) => panic!("Unknown maze format: {}", other),
+        },
+        "verify" => {
+            // Checks the seed as given, without moving on to later seeds.
+            let (maze, _) = maze_from_seed(seed, variant);
+
+            if let Err(e) = verify_maze(&maze) {
+                eprintln!("{}", e);
+                exit(1);
+            }
+
+            println!(
+                "Unique shortest route with a distance of {}.",
+                maze.route_distance
+            );
         }
         "validate" => {
             let mut buffer = String::new();
@@ -35,7 +76,7 @@ fn main() {
                 .parse::<usize>()
                 .graceful_expect("Invalid input. Expected positive integer");
 
-            if input == length {
+            if input == answer(&maze, variant) {
                 exit(0);
             } else {
                 exit(1);
@@ -63,9 +104,141 @@ impl<T, E> GracefulExpect<T> for Result<T, E> {
 
 type Graph = BTreeMap<NodeId, Node>;
 type NodeId = usize;
+type Weight = usize;
 
 struct Node {
-    neighbors: Vec<NodeId>,
+    neighbors: Vec<Edge>,
+}
+
+#[derive(Clone, Copy)]
+struct Edge {
+    to: NodeId,
+    weight: Weight,
+}
+
+/// Which flavour of maze to generate. Selected with the `MAZE_VARIANT`
+/// environment variable so that the generator and judge agree.
+#[derive(Clone, Copy)]
+struct Variant {
+    /// Corridors have a length other than 1 and the answer is the total
+    /// length of the route rather than the number of nodes on it.
+    weighted: bool,
+    /// Some corridors can only be travelled in one direction.
+    directed: bool,
+}
+
+impl Variant {
+    fn from_env() -> Variant {
+        let (weighted, directed) = match std::env::var("MAZE_VARIANT").as_deref() {
+            Err(_) | Ok("plain") => (false, false),
+            Ok("weighted") => (true, false),
+            Ok("directed") => (false, true),
+            Ok("weighted-directed") => (true, true),
+            Ok(other) => panic!("Unknown maze variant: {}", other),
+        };
+
+        Variant { weighted, directed }
+    }
+}
+
+struct Maze {
+    graph: Graph,
+    start: NodeId,
+    end: NodeId,
+    id_shuffle_map: BTreeMap<NodeId, NodeId>,
+
+    /// Number of web nodes hung off each node of the correct route, in
+    /// order from start to end.
+    web_sizes: Vec<usize>,
+
+    /// Sum of the corridor weights along the route the maze was built around.
+    route_distance: Weight,
+}
+
+/// Creates the maze for a seed. The RNG is returned so that other formats
+/// can continue drawing from it.
+fn maze_from_seed(seed: u64, variant: Variant) -> (Maze, ChaChaRng) {
+    let mut rng = ChaChaRng::seed_from_u64(seed);
+
+    let length = rng.gen_range(20..=30);
+
+    let maze = create_maze(length, variant, &mut rng);
+
+    (maze, rng)
+}
+
+/// Creates a maze from the seed, moving on to the next seed if the maze
+/// fails verification.
+fn generate_maze(seed: u64, variant: Variant) -> (Maze, ChaChaRng) {
+    let (maze, rng) = maze_from_seed(seed, variant);
+
+    if verify_maze(&maze).is_err() {
+        return generate_maze(seed.wrapping_add(1), variant);
+    }
+
+    (maze, rng)
+}
+
+/// Checks the graph as it is published (after shuffling IDs) rather than
+/// trusting the construction: the route from 0 to 1 must be the one the maze
+/// was built around, and no other route may be as short.
+fn verify_maze(maze: &Maze) -> Result<(), String> {
+    let graph = published_graph(maze);
+
+    if graph.len() != maze.graph.len() {
+        return Err("Some nodes were published with the same ID.".to_string());
+    }
+
+    let (distance, routes) =
+        count_shortest_paths(&graph, 0, 1).ok_or("The end is not reachable from the start.")?;
+
+    if distance != maze.route_distance {
+        return Err(format!(
+            "Shortest route is {} but the maze was built around a route of {}.",
+            distance, maze.route_distance
+        ));
+    }
+
+    if routes != 1 {
+        return Err(format!("There are {} shortest routes.", routes));
+    }
+
+    Ok(())
+}
+
+/// Rebuilds the graph with the shuffled IDs that contestants see. Nodes that
+/// share an ID are merged, as they would be by anyone parsing the input.
+fn published_graph(maze: &Maze) -> Graph {
+    let mut graph = Graph::new();
+
+    for (&node_id, node) in &maze.graph {
+        let neighbors = node.neighbors.iter().map(|edge| Edge {
+            to: maze.id_shuffle_map[&edge.to],
+            weight: edge.weight,
+        });
+
+        graph
+            .entry(maze.id_shuffle_map[&node_id])
+            .or_insert(Node { neighbors: vec![] })
+            .neighbors
+            .extend(neighbors);
+    }
+
+    graph
+}
+
+/// Solves the maze rather than trusting how it was constructed.
+/// Unweighted mazes count the nodes on the route (including the start and
+/// end), weighted mazes sum the corridor lengths.
+fn answer(maze: &Maze, variant: Variant) -> usize {
+    let distance = shortest_distance(&maze.graph, maze.start, maze.end)
+        .expect("The end of the maze should be reachable from the start.");
+
+    if variant.weighted {
+        distance
+    } else {
+        distance + 1
+    }
 }
 
 fn print_graph(
@@ -73,24 +246,37 @@ fn print_graph(
     id_shuffle_map: Option<&BTreeMap<NodeId, NodeId>>,
     start: NodeId,
     end: NodeId,
+    variant: Variant,
 ) {
-    print_node(&start, &graph[&start], id_shuffle_map);
-    print_node(&end, &graph[&end], id_shuffle_map);
+    print_node(&start, &graph[&start], id_shuffle_map, variant);
+    print_node(&end, &graph[&end], id_shuffle_map, variant);
 
     for (node_id, node) in graph.iter() {
         if *node_id == start || *node_id == end {
             continue;
         }
 
-        print_node(node_id, node, id_shuffle_map);
+        print_node(node_id, node, id_shuffle_map, variant);
     }
 }
 
-fn print_node(node_id: &NodeId, node: &Node, id_shuffle_map: Option<&BTreeMap<NodeId, NodeId>>) {
+fn print_node(
+    node_id: &NodeId,
+    node: &Node,
+    id_shuffle_map: Option<&BTreeMap<NodeId, NodeId>>,
+    variant: Variant,
+) {
     let neighbours = node
         .neighbors
         .iter()
-        .map(|&id| apply_shuffle_map(id, id_shuffle_map).to_string())
+        .map(|edge| {
+            let id = apply_shuffle_map(edge.to, id_shuffle_map);
+            if variant.weighted {
+                format!("{} ({})", id, edge.weight)
+            } else {
+                id.to_string()
+            }
+        })
         .collect::<Vec<_>>()
         .join(", ");
 
@@ -117,18 +303,62 @@ fn create_node(graph: &mut Graph) -> NodeId {
     id
 }
 
-fn link_nodes(graph: &mut Graph, a: NodeId, b: NodeId) {
-    graph.get_mut(&a).unwrap().neighbors.push(b);
-    graph.get_mut(&b).unwrap().neighbors.push(a);
+fn link_one_way(graph: &mut Graph, from: NodeId, to: NodeId, weight: Weight) {
+    graph
+        .get_mut(&from)
+        .unwrap()
+        .neighbors
+        .push(Edge { to, weight });
+}
+
+fn link_nodes(graph: &mut Graph, a: NodeId, b: NodeId, weight: Weight) {
+    link_one_way(graph, a, b, weight);
+    link_one_way(graph, b, a, weight);
+}
+
+fn random_weight<R: Rng>(variant: Variant, rng: &mut R) -> Weight {
+    if variant.weighted {
+        rng.gen_range(1..=9)
+    } else {
+        1
+    }
+}
+
+/// Links two nodes with a corridor. In directed mazes the corridor may
+/// randomly be one-way in either direction.
+fn link_random<R: Rng>(graph: &mut Graph, a: NodeId, b: NodeId, variant: Variant, rng: &mut R) {
+    let weight = random_weight(variant, rng);
+
+    if !variant.directed {
+        link_nodes(graph, a, b, weight);
+        return;
+    }
+
+    match rng.gen_range(0..3) {
+        0 => link_nodes(graph, a, b, weight),
+        1 => link_one_way(graph, a, b, weight),
+        _ => link_one_way(graph, b, a, weight),
+    }
 }
 
 /// Create a chain of nodes and link them together in a chain.
-fn create_chain(graph: &mut Graph, length: usize) -> Vec<NodeId> {
+/// In directed mazes the chain can only be travelled from start to end.
+fn create_chain<R: Rng>(
+    graph: &mut Graph,
+    length: usize,
+    variant: Variant,
+    rng: &mut R,
+) -> Vec<NodeId> {
     let mut node_ids = vec![];
     for _ in 0..length {
         let node_id = create_node(graph);
         if let Some(&last_node) = node_ids.last() {
-            link_nodes(graph, last_node, node_id);
+            let weight = random_weight(variant, rng);
+            if variant.directed {
+                link_one_way(graph, last_node, node_id, weight);
+            } else {
+                link_nodes(graph, last_node, node_id, weight);
+            }
         }
         node_ids.push(node_id);
     }
@@ -136,7 +366,7 @@ fn create_chain(graph: &mut Graph, length: usize) -> Vec<NodeId> {
 }
 
 /// Create several nodes and link them together randomly.
-fn create_web<R: Rng>(graph: &mut Graph, rng: &mut R) -> Vec<NodeId> {
+fn create_web<R: Rng>(graph: &mut Graph, variant: Variant, rng: &mut R) -> Vec<NodeId> {
     let node_count = rng.gen_range(10..=20);
     let mut node_ids = vec![];
 
@@ -161,7 +391,7 @@ fn create_web<R: Rng>(graph: &mut Graph, rng: &mut R) -> Vec<NodeId> {
                 .clone()
                 .unwrap();
 
-            link_nodes(graph, *node_id, neighbor_id);
+            link_random(graph, *node_id, neighbor_id, variant, rng);
         }
     }
 
@@ -191,7 +421,9 @@ fn create_id_shuffle_map<R: Rng>(
     let mut node_ids = graph.keys().cloned().collect::<Vec<_>>();
     node_ids.retain(|&id| id != start && id != end);
 
-    let mut shuffled_node_ids = node_ids.clone();
+    // IDs 0 and 1 are taken by the start and end, so everything else is
+    // given one of the remaining IDs.
+    let mut shuffled_node_ids = (2..graph.len()).collect::<Vec<_>>();
     for i in 0..shuffled_node_ids.len() {
         let j = rng.gen_range(0..shuffled_node_ids.len());
         swap_vec_elements(&mut shuffled_node_ids, i, j);
@@ -204,20 +436,35 @@ fn create_id_shuffle_map<R: Rng>(
     map
 }
 
-fn create_maze<R: Rng>(route_length: usize, rng: &mut R) {
+fn create_maze<R: Rng>(route_length: usize, variant: Variant, rng: &mut R) -> Maze {
     assert!(route_length > 1);
 
     let mut graph = Graph::new();
 
     // Creates the correct path through the maze.
-    let chain_node_ids = create_chain(&mut graph, route_length);
+    let chain_node_ids = create_chain(&mut graph, route_length, variant, rng);
+
+    let route_distance = chain_node_ids
+        .windows(2)
+        .map(|pair| {
+            graph[&pair[0]]
+                .neighbors
+                .iter()
+                .find(|edge| edge.to == pair[1])
+                .unwrap()
+                .weight
+        })
+        .sum();
 
     // Create several webs and correct them to single points in the main chain.
     // This ensures that the maze in complicated but there is only one solution
     // as all of the webs will be self-contained and not create any alternative
     // paths to the end of the maze.
+    let mut web_sizes = vec![];
+
     for chain_node_id in &chain_node_ids {
-        let mut web_node_ids = create_web(&mut graph, rng);
+        let mut web_node_ids = create_web(&mut graph, variant, rng);
+        web_sizes.push(web_node_ids.len());
         let links = rng.gen_range(1..web_node_ids.len());
 
         for _ in 0..links {
@@ -227,7 +474,7 @@ fn create_maze<R: Rng>(route_length: usize, rng: &mut R) {
                 .next()
                 .clone()
                 .unwrap();
-            link_nodes(&mut graph, *chain_node_id, web_node_id);
+            link_random(&mut graph, *chain_node_id, web_node_id, variant, rng);
         }
     }
 
@@ -238,5 +485,12 @@ fn create_maze<R: Rng>(route_length: usize, rng: &mut R) {
     // Shuffles IDs so that the main chain doesn't have consecutive IDs.
     let id_shuffle_map = create_id_shuffle_map(&graph, rng, start, end);
 
-    print_graph(&graph, Some(&id_shuffle_map), start, end);
-}
\ No newline at end of file
+    Maze {
+        graph,
+        start,
+        end,
+        id_shuffle_map,
+        web_sizes,
+        route_distance,
+    }
+}
@@ -0,0 +1,62 @@
+use std::{
+    cmp::Reverse,
+    collections::{BTreeMap, BinaryHeap},
+};
+
+use crate::{Graph, NodeId, Weight};
+
+/// Dijkstra's algorithm over the maze's corridors. For unweighted mazes every
+/// corridor has a weight of 1 so this is equivalent to a breadth first search.
+///
+/// Returns None if the end cannot be reached from the start.
+pub fn shortest_distance(graph: &Graph, start: NodeId, end: NodeId) -> Option<Weight> {
+    count_shortest_paths(graph, start, end).map(|(distance, _)| distance)
+}
+
+/// Finds the length of the shortest route from start to end along with how
+/// many distinct routes have that length. The count saturates rather than
+/// overflowing.
+///
+/// Returns None if the end cannot be reached from the start.
+pub fn count_shortest_paths(graph: &Graph, start: NodeId, end: NodeId) -> Option<(Weight, usize)> {
+    let mut distances: BTreeMap<NodeId, Weight> = BTreeMap::new();
+    let mut counts: BTreeMap<NodeId, usize> = BTreeMap::new();
+    let mut queue = BinaryHeap::new();
+
+    distances.insert(start, 0);
+    counts.insert(start, 1);
+    queue.push(Reverse((0, start)));
+
+    while let Some(Reverse((distance, node_id))) = queue.pop() {
+        if distance > distances[&node_id] {
+            continue;
+        }
+
+        // Every corridor has a positive weight so all routes into a node
+        // have been counted by the time it leaves the queue.
+        if node_id == end {
+            return Some((distance, counts[&node_id]));
+        }
+
+        let count = counts[&node_id];
+
+        for edge in &graph[&node_id].neighbors {
+            let next = distance + edge.weight;
+
+            match distances.get(&edge.to) {
+                Some(&d) if next > d => {}
+                Some(&d) if next == d => {
+                    let total = counts[&edge.to].saturating_add(count);
+                    counts.insert(edge.to, total);
+                }
+                _ => {
+                    distances.insert(edge.to, next);
+                    counts.insert(edge.to, count);
+                    queue.push(Reverse((next, edge.to)));
+                }
+            }
+        }
+    }
+
+    None
+}
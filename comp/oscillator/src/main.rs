@@ -7,6 +7,7 @@ use std::{
     process::exit,
 };
 
+use numeric_answer::{NumericAnswer, Tolerance};
 use rand::{Rng, SeedableRng};
 
 use rand_chacha::ChaChaRng;
@@ -34,15 +35,22 @@ fn main() {
             let mut buffer = String::new();
             std::io::stdin().read_line(&mut buffer).unwrap();
 
-            let input = buffer
-                .trim()
-                .parse::<f64>()
-                .graceful_expect("Invalid input. Expected number.");
+            let answer = NumericAnswer {
+                decimal_places: None,
+                tolerance: Tolerance::Absolute(0.05),
+                range: None,
+            }
+            .with_env_overrides();
+
+            let input = answer.parse(buffer.trim()).unwrap_or_else(|e| {
+                eprintln!("{}", e);
+                exit(1);
+            });
 
             system = simulate(system);
             let solution = system.bodies[0].x.0;
 
-            if (input - solution).abs() <= 0.05 {
+            if answer.matches(input, solution) {
                 exit(0);
             } else {
                 exit(1);
@@ -52,22 +60,6 @@ fn main() {
     }
 }
 
-trait GracefulExpect<T> {
-    fn graceful_expect(self, message: &str) -> T;
-}
-
-impl<T, E> GracefulExpect<T> for Result<T, E> {
-    fn graceful_expect(self, message: &str) -> T {
-        match self {
-            Ok(v) => v,
-            Err(_) => {
-                eprintln!("{}", message);
-                exit(1);
-            }
-        }
-    }
-}
-
 type BodyId = usize;
 
 #[derive(Clone)]
@@ -4,11 +4,16 @@ use std::{
     process::exit,
 };
 
-use primes::is_prime;
+use parse::parse_tx;
+use prime_table::prime_count;
+use primes::factors;
 use rand::{Rng, SeedableRng};
 
 use rand_chacha::ChaChaRng;
 
+mod parse;
+mod prime_table;
+
 fn main() {
     let args = std::env::args().collect::<Vec<String>>();
 
@@ -20,21 +25,30 @@ fn main() {
 
     let mut rng = ChaChaRng::seed_from_u64(seed);
 
-    let n = rng.gen_range(700..10_000);
+    let n = rng.gen_range(700..max_number());
+
+    let mode = Mode::from_env();
 
     match args[1].as_str() {
-        "generate" => {
-            println!("{}", n);
-        }
+        "generate" => match mode {
+            Mode::Encode => println!("{}", n),
+            Mode::Decode => println!("{}", to_tx(n)),
+        },
         "validate" => {
             let mut buffer = String::new();
             std::io::stdin().read_line(&mut buffer).unwrap();
 
             let input = buffer.trim();
 
-            is_valid_tx_chars(input.to_string()).graceful_unwrap();
-
-            let input = parse_tx(input.to_string()).graceful_unwrap();
+            let input = match mode {
+                Mode::Encode => parse_tx(input, strict())
+                    .map_err(|e| e.to_string())
+                    .graceful_unwrap(),
+                Mode::Decode => input
+                    .parse::<u64>()
+                    .map_err(|_| "Expected a positive integer.".to_string())
+                    .graceful_unwrap(),
+            };
 
             if input == n {
                 exit(0);
@@ -46,6 +60,42 @@ fn main() {
     }
 }
 
+/// The generator picks numbers below this, set by `XENOTATION_MAX` and
+/// 10,000 by default.
+fn max_number() -> u64 {
+    match std::env::var("XENOTATION_MAX") {
+        Ok(max) => max
+            .parse::<u64>()
+            .expect("XENOTATION_MAX should be an integer."),
+        Err(_) => 10_000,
+    }
+}
+
+/// Whether submissions must be in canonical form, set with
+/// `XENOTATION_STRICT=1`.
+fn strict() -> bool {
+    std::env::var("XENOTATION_STRICT").is_ok_and(|v| v == "1")
+}
+
+/// Selected with the `XENOTATION_MODE` environment variable.
+#[derive(Clone, Copy)]
+enum Mode {
+    /// The input is an integer and the answer is its xenotation.
+    Encode,
+    /// The input is a xenotation string and the answer is the integer.
+    Decode,
+}
+
+impl Mode {
+    fn from_env() -> Mode {
+        match std::env::var("XENOTATION_MODE").as_deref() {
+            Err(_) | Ok("encode") => Mode::Encode,
+            Ok("decode") => Mode::Decode,
+            Ok(other) => panic!("Unknown xenotation mode: {}", other),
+        }
+    }
+}
+
 trait GracefulUnwrap<T> {
     fn graceful_unwrap(self) -> T;
 }
@@ -62,68 +112,45 @@ impl<T> GracefulUnwrap<T> for Result<T, String> {
     }
 }
 
-fn is_valid_tx_chars(s: String) -> Result<(), String> {
-    for c in s.chars() {
-        if c != ':' && c != '(' && c != ')' {
-            return Err("Invalid character(s).".to_string());
-        }
-    }
-    Ok(())
-}
-
-fn parse_tx(tx: String) -> Result<u64, String> {
-    let mut child_call_buffer = "".to_string();
-    let mut n = 1;
-    let mut brack_depth = 0;
-
-    for c in tx.chars() {
-        if c == ')' {
-            brack_depth -= 1;
-            if brack_depth == 0 {
-                run_recusivly_and_flush_buffer(&mut child_call_buffer, &mut n)?;
-            }
-        }
+/// Encodes n in its canonical form: a `:` for each factor of 2 followed by
+/// the index of each odd prime factor in brackets, smallest prime first.
+/// Panics if n has a prime factor past the sieve limit.
+fn to_tx(n: u64) -> String {
+    let mut tx = String::new();
 
-        if brack_depth > 0 {
-            child_call_buffer.push(c);
-        } else if c == ':' {
-            n <<= 1;
-        }
+    if n < 2 {
+        return tx;
+    }
 
-        if c == '(' {
-            brack_depth += 1
+    for p in factors(n) {
+        if p == 2 {
+            tx.push(':');
+        } else {
+            tx.push('(');
+            let index = prime_count(p).expect("Prime factor is past the sieve limit.");
+            tx.push_str(&to_tx(index));
+            tx.push(')');
         }
     }
-    if brack_depth != 0 {
-        return Err("Unbalanced brackets.".to_string());
-    }
-    Ok(n as u64)
-}
 
-fn run_recusivly_and_flush_buffer(
-    child_call_buffer: &mut String,
-    n: &mut u64,
-) -> Result<(), String> {
-    let child = parse_tx(child_call_buffer.clone())?;
-    *child_call_buffer = "".to_string();
-    *n *= nth_prime(child);
-    Ok(())
+    tx
 }
 
-fn nth_prime(n: u64) -> u64 {
-    let mut i = 0;
-    let mut p = 0;
-    while i < n {
-        p = next_prime(p);
-        i += 1;
-    }
-    p
-}
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-fn next_prime(n: u64) -> u64 {
-    let mut p = n + 1;
-    while !is_prime(p) {
-        p += 1;
+    /// Round trips every number the generator can produce through the encoder
+    /// and parser.
+    #[test]
+    fn round_trip() {
+        for n in 1..max_number() {
+            let tx = to_tx(n);
+
+            match parse_tx(&tx, true) {
+                Ok(m) => assert_eq!(m, n, "{} encoded as {}", n, tx),
+                Err(e) => panic!("{} encoded as {} which does not parse: {}", n, tx, e),
+            }
+        }
     }
-    p
 }
@@ -0,0 +1,145 @@
+use std::fmt;
+
+use crate::prime_table::nth_prime;
+
+/// Deeper nesting can't produce a prime within the sieve limit anyway, and
+/// limiting it keeps adversarial inputs from overflowing the stack.
+const MAX_DEPTH: usize = 32;
+
+#[derive(Debug)]
+pub struct ParseError {
+    /// Column of the offending character, counting from 1.
+    column: usize,
+    message: String,
+    /// Value of the outermost product up to the offending character.
+    value_so_far: u64,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Column {}: {} (value so far: {})",
+            self.column, self.message, self.value_so_far
+        )
+    }
+}
+
+/// Evaluates a xenotation string. In strict mode the string must also be in
+/// the canonical form produced by `to_tx`: colons first, then bracketed primes
+/// in increasing order, with 2 always written as `:`.
+pub fn parse_tx(tx: &str, strict: bool) -> Result<u64, ParseError> {
+    let mut parser = Parser {
+        chars: tx.chars().collect(),
+        position: 0,
+        depth: 0,
+        strict,
+    };
+
+    let n = parser.product()?;
+
+    // product() only stops early at a closing bracket.
+    if parser.position < parser.chars.len() {
+        return Err(parser.error(parser.position + 1, "Unmatched `)`.", n));
+    }
+
+    Ok(n)
+}
+
+struct Parser {
+    chars: Vec<char>,
+    position: usize,
+    depth: usize,
+    strict: bool,
+}
+
+impl Parser {
+    fn error(&self, column: usize, message: &str, value_so_far: u64) -> ParseError {
+        ParseError {
+            column,
+            message: message.to_string(),
+            value_so_far,
+        }
+    }
+
+    /// Multiplies together factors until the end of the input or a closing
+    /// bracket, which is left for the caller. Values that don't fit in a u64
+    /// are rejected rather than wrapping around.
+    fn product(&mut self) -> Result<u64, ParseError> {
+        let mut n = 1;
+        let mut last_prime = None;
+
+        while let Some(&c) = self.chars.get(self.position) {
+            let column = self.position + 1;
+
+            match c {
+                ':' => {
+                    if self.strict && last_prime.is_some() {
+                        return Err(self.error(
+                            column,
+                            "Canonical form has all `:` before any brackets.",
+                            n,
+                        ));
+                    }
+
+                    n = n
+                        .checked_mul(2)
+                        .ok_or_else(|| self.error(column, "Number is too large.", n))?;
+                    self.position += 1;
+                }
+                '(' => {
+                    if self.depth == MAX_DEPTH {
+                        return Err(self.error(column, "Brackets are nested too deeply.", n));
+                    }
+
+                    self.position += 1;
+                    self.depth += 1;
+
+                    // Errors report the outermost value, so replace the inner
+                    // product's value with ours on the way out.
+                    let index = self.product().map_err(|mut e| {
+                        e.value_so_far = n;
+                        e
+                    })?;
+
+                    self.depth -= 1;
+
+                    if self.chars.get(self.position) != Some(&')') {
+                        return Err(self.error(column, "Unclosed `(`.", n));
+                    }
+                    self.position += 1;
+
+                    let p = nth_prime(index)
+                        .ok_or_else(|| self.error(column, "Number is too large.", n))?;
+
+                    if self.strict && p == 2 {
+                        return Err(self.error(
+                            column,
+                            "Canonical form writes 2 as `:` rather than `()`.",
+                            n,
+                        ));
+                    }
+
+                    if self.strict && last_prime.is_some_and(|last| p < last) {
+                        return Err(self.error(
+                            column,
+                            "Canonical form has primes in increasing order.",
+                            n,
+                        ));
+                    }
+
+                    last_prime = Some(p);
+                    n = n
+                        .checked_mul(p)
+                        .ok_or_else(|| self.error(column, "Number is too large.", n))?;
+                }
+                ')' => break,
+                other => {
+                    return Err(self.error(column, &format!("Invalid character `{}`.", other), n))
+                }
+            }
+        }
+
+        Ok(n)
+    }
+}
@@ -0,0 +1,93 @@
+use std::cell::RefCell;
+
+/// Primes are never sieved beyond this bound, which keeps adversarial inputs
+/// such as deeply nested brackets from exhausting the judge's memory.
+pub const SIEVE_LIMIT: u64 = 1 << 25;
+
+/// All primes up to `limit`, grown by doubling the limit and sieving again
+/// whenever a larger prime is needed.
+struct PrimeTable {
+    limit: u64,
+    primes: Vec<u64>,
+}
+
+impl PrimeTable {
+    /// Sieves up to at least `limit`. Returns false if that is past the
+    /// sieve limit.
+    fn cover(&mut self, limit: u64) -> bool {
+        if limit > SIEVE_LIMIT {
+            return false;
+        }
+
+        if self.limit < limit {
+            self.limit = limit.next_power_of_two().clamp(1 << 10, SIEVE_LIMIT);
+            self.primes = sieve(self.limit);
+        }
+
+        true
+    }
+}
+
+thread_local! {
+    static TABLE: RefCell<PrimeTable> = const {
+        RefCell::new(PrimeTable {
+            limit: 0,
+            primes: vec![],
+        })
+    };
+}
+
+fn sieve(limit: u64) -> Vec<u64> {
+    let limit = limit as usize;
+    let mut composite = vec![false; limit + 1];
+    let mut primes = vec![];
+
+    for i in 2..=limit {
+        if composite[i] {
+            continue;
+        }
+
+        primes.push(i as u64);
+
+        for multiple in (i * i..=limit).step_by(i) {
+            composite[multiple] = true;
+        }
+    }
+
+    primes
+}
+
+/// The nth prime, counting 2 as the 1st. Returns None for 0 or if the prime
+/// is past the sieve limit.
+pub fn nth_prime(n: u64) -> Option<u64> {
+    let index = n.checked_sub(1)? as usize;
+
+    TABLE.with(|table| {
+        let mut table = table.borrow_mut();
+
+        while table.primes.len() <= index {
+            if table.limit >= SIEVE_LIMIT {
+                return None;
+            }
+
+            let limit = (table.limit * 2).max(2);
+            table.cover(limit);
+        }
+
+        Some(table.primes[index])
+    })
+}
+
+/// The number of primes less than or equal to x, so for a prime p this is its
+/// index: `nth_prime(prime_count(p)) == p`. Returns None past the sieve limit.
+pub fn prime_count(x: u64) -> Option<u64> {
+    TABLE.with(|table| {
+        let mut table = table.borrow_mut();
+
+        if !table.cover(x) {
+            return None;
+        }
+
+        Some(table.primes.partition_point(|&p| p <= x) as u64)
+    })
+}
@@ -0,0 +1,243 @@
+//! Checks numeric answers for the judges that expect a decimal number, so
+//! they all agree on what a valid number looks like and report why an answer
+//! was rejected.
+
+/// How close an answer has to be to the expected value.
+#[derive(Clone, Copy, Debug)]
+pub enum Tolerance {
+    /// Within this distance of the expected value.
+    Absolute(f64),
+    /// Within this fraction of the expected value.
+    Relative(f64),
+    /// Equal to the expected value rounded to the required number of decimal
+    /// places. Either neighbour is accepted when the value is exactly halfway.
+    Rounded,
+}
+
+/// Allows for the error in converting decimal strings to floats.
+const EPSILON: f64 = 1e-9;
+
+#[derive(Clone, Debug)]
+pub struct NumericAnswer {
+    /// The exact number of digits required after the decimal point, or None
+    /// to accept any number.
+    pub decimal_places: Option<usize>,
+    pub tolerance: Tolerance,
+    /// Inclusive bounds that any answer must fall within.
+    pub range: Option<(f64, f64)>,
+}
+
+impl NumericAnswer {
+    /// Lets the decimal places and tolerance be changed without rebuilding
+    /// through `ANSWER_DECIMAL_PLACES` (a count, or `any`) and
+    /// `ANSWER_TOLERANCE` (`rounded`, an absolute distance such as `0.01`, or
+    /// a relative one such as `1%`).
+    pub fn with_env_overrides(mut self) -> NumericAnswer {
+        if let Ok(places) = std::env::var("ANSWER_DECIMAL_PLACES") {
+            self.decimal_places = match places.as_str() {
+                "any" => None,
+                places => Some(
+                    places
+                        .parse()
+                        .expect("ANSWER_DECIMAL_PLACES should be a count or `any`."),
+                ),
+            };
+        }
+
+        if let Ok(tolerance) = std::env::var("ANSWER_TOLERANCE") {
+            self.tolerance = parse_tolerance(&tolerance)
+                .expect("ANSWER_TOLERANCE should be `rounded`, a number or a percentage.");
+        }
+
+        if let Tolerance::Rounded = self.tolerance {
+            assert!(
+                self.decimal_places.is_some(),
+                "A rounded tolerance needs a number of decimal places."
+            );
+        }
+
+        self
+    }
+
+    /// Parses a submitted answer. Only plain decimals such as `-12.50` are
+    /// accepted: no exponents, leading `+`, `inf` or `NaN`.
+    pub fn parse(&self, input: &str) -> Result<f64, String> {
+        let digits = input.strip_prefix('-').unwrap_or(input);
+
+        let (whole, fraction) = match digits.split_once('.') {
+            Some((whole, fraction)) => (whole, Some(fraction)),
+            None => (digits, None),
+        };
+
+        let is_digits = |s: &str| !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit());
+
+        if !is_digits(whole) || !fraction.is_none_or(is_digits) {
+            return Err(format!(
+                "Expected a plain decimal number such as `12.34` but found `{}`.",
+                input
+            ));
+        }
+
+        let places = fraction.map_or(0, str::len);
+
+        if let Some(required) = self.decimal_places {
+            if places != required {
+                return Err(format!(
+                    "Expected exactly {} decimal places but found {}.",
+                    required, places
+                ));
+            }
+        }
+
+        let value = input.parse::<f64>().map_err(|e| e.to_string())?;
+
+        if let Some((min, max)) = self.range {
+            if value < min || value > max {
+                return Err(format!("Expected a number from {} to {}.", min, max));
+            }
+        }
+
+        Ok(value)
+    }
+
+    /// Whether a parsed answer is close enough to the expected value.
+    pub fn matches(&self, value: f64, expected: f64) -> bool {
+        let allowed = match self.tolerance {
+            Tolerance::Absolute(distance) => distance,
+            Tolerance::Relative(fraction) => fraction * expected.abs(),
+            Tolerance::Rounded => {
+                let places = self.decimal_places.unwrap_or(0) as i32;
+                0.5 * 10f64.powi(-places)
+            }
+        };
+
+        (value - expected).abs() <= allowed + EPSILON
+    }
+}
+
+fn parse_tolerance(tolerance: &str) -> Option<Tolerance> {
+    if tolerance == "rounded" {
+        return Some(Tolerance::Rounded);
+    }
+
+    // Only plain non-negative numbers make sense as a distance.
+    let parse = |s: &str| {
+        s.parse::<f64>()
+            .ok()
+            .filter(|value| value.is_finite() && *value >= 0.0)
+    };
+
+    match tolerance.strip_suffix('%') {
+        Some(percent) => Some(Tolerance::Relative(parse(percent)? / 100.0)),
+        None => Some(Tolerance::Absolute(parse(tolerance)?)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn answer(decimal_places: Option<usize>, tolerance: Tolerance) -> NumericAnswer {
+        NumericAnswer {
+            decimal_places,
+            tolerance,
+            range: Some((-100.0, 100.0)),
+        }
+    }
+
+    #[test]
+    fn parse_accepts_plain_decimals() {
+        let answer = answer(Some(2), Tolerance::Rounded);
+
+        assert_eq!(answer.parse("12.50"), Ok(12.5));
+        assert_eq!(answer.parse("-0.25"), Ok(-0.25));
+        assert_eq!(answer.parse("100.00"), Ok(100.0));
+    }
+
+    #[test]
+    fn parse_rejects_other_numbers() {
+        let answer = answer(Some(2), Tolerance::Rounded);
+
+        for input in [
+            "1e2", "1.00e1", "+1.00", "5.", ".50", "-", "", "inf", "NaN", "1,00", " 1.00",
+        ] {
+            assert!(answer.parse(input).is_err(), "{}", input);
+        }
+    }
+
+    #[test]
+    fn parse_rejects_wrong_decimal_places() {
+        let answer = answer(Some(2), Tolerance::Rounded);
+
+        for input in ["1", "1.5", "1.500"] {
+            assert!(answer.parse(input).is_err(), "{}", input);
+        }
+
+        let any = NumericAnswer {
+            decimal_places: None,
+            ..answer
+        };
+
+        for input in ["1", "1.5", "1.500"] {
+            assert!(any.parse(input).is_ok(), "{}", input);
+        }
+    }
+
+    #[test]
+    fn parse_rejects_values_out_of_range() {
+        let answer = answer(Some(2), Tolerance::Rounded);
+
+        assert!(answer.parse("100.01").is_err());
+        assert!(answer.parse("-100.01").is_err());
+        assert!(answer.parse("-100.00").is_ok());
+    }
+
+    #[test]
+    fn rounded_accepts_either_neighbour_halfway() {
+        let answer = answer(Some(2), Tolerance::Rounded);
+
+        assert!(answer.matches(1.00, 1.005));
+        assert!(answer.matches(1.01, 1.005));
+        assert!(!answer.matches(0.99, 1.005));
+        assert!(!answer.matches(1.02, 1.005));
+    }
+
+    #[test]
+    fn absolute_accepts_up_to_the_distance() {
+        let answer = answer(None, Tolerance::Absolute(0.01));
+
+        assert!(answer.matches(10.01, 10.0));
+        assert!(answer.matches(9.99, 10.0));
+        assert!(!answer.matches(10.02, 10.0));
+        assert!(!answer.matches(9.98, 10.0));
+    }
+
+    #[test]
+    fn relative_accepts_up_to_the_fraction() {
+        let answer = answer(None, Tolerance::Relative(0.01));
+
+        assert!(answer.matches(202.0, 200.0));
+        assert!(answer.matches(-202.0, -200.0));
+        assert!(!answer.matches(202.1, 200.0));
+        assert!(!answer.matches(-197.9, -200.0));
+    }
+
+    #[test]
+    fn parse_tolerance_reads_env_values() {
+        assert!(matches!(
+            parse_tolerance("rounded"),
+            Some(Tolerance::Rounded)
+        ));
+        assert!(matches!(parse_tolerance("0.01"), Some(Tolerance::Absolute(d)) if d == 0.01));
+        assert!(matches!(parse_tolerance("1%"), Some(Tolerance::Relative(f)) if f == 0.01));
+    }
+
+    #[test]
+    fn parse_tolerance_rejects_malformed_env_values() {
+        for tolerance in [
+            "", "%", "Rounded", "abc", "1%%", "%1", "-0.01", "-1%", "inf", "NaN%",
+        ] {
+            assert!(parse_tolerance(tolerance).is_none(), "{}", tolerance);
+        }
+    }
+}